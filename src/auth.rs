@@ -0,0 +1,55 @@
+use std::fmt;
+
+/// An API key that does not give itself away: its `Debug` and `Display`
+/// output is `***`.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ApiKey(String);
+
+impl ApiKey {
+    pub fn new(key: &str) -> ApiKey {
+        ApiKey(key.to_string())
+    }
+
+    /// The key itself, for sending it. Keep it out of anything that is
+    /// logged or stored.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for ApiKey {
+    fn from(key: &str) -> Self {
+        ApiKey::new(key)
+    }
+}
+
+impl From<String> for ApiKey {
+    fn from(key: String) -> Self {
+        ApiKey(key)
+    }
+}
+
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+impl fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("***")
+    }
+}
+
+/// Where [`crate::NewsAPI`] puts the API key in each request.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum KeyPlacement {
+    /// An `X-Api-Key` header.
+    #[default]
+    XApiKeyHeader,
+    /// An `Authorization: Bearer <key>` header.
+    BearerHeader,
+    /// The `apiKey` query parameter. The key then becomes part of the URL,
+    /// which proxies and servers tend to log.
+    QueryParameter,
+}
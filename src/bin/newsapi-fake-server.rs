@@ -0,0 +1,761 @@
+//! A fake NewsAPI serving fixture data, for developing and testing against
+//! [`newsapi::NewsAPI`] without a real key.
+//!
+//! ```text
+//! newsapi-fake-server <fixtures-dir> [--addr 127.0.0.1:8080] [--key KEY]... [--max-results N]
+//! ```
+//!
+//! The fixtures directory holds `articles.json`, an array of articles as the
+//! API returns them, and `sources.json`, an array of sources. An article's
+//! country, category and language are those of the source its `source.id`
+//! refers to, and a `sources` parameter may only name sources listed there.
+//! Point a client at it with
+//! `NewsAPI::base_url("http://127.0.0.1:8080/v2/")`.
+//!
+//! Requests must carry one of the `--key` values, in an `X-Api-Key` or
+//! `Authorization` header or the `apiKey` parameter; without `--key` any key
+//! is accepted. `--max-results` mimics the developer plan's ceiling on how
+//! deep a query can be paged.
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use newsapi::{Category, Country, Language, SearchIn, SortBy};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process;
+use std::str::FromStr;
+use tiny_http::{Header, Request, Response, Server};
+use url::Url;
+
+struct Config {
+    addr: String,
+    keys: Vec<String>,
+    max_results: Option<usize>,
+    articles: Vec<Value>,
+    sources: Vec<Value>,
+}
+
+/// A failed request, answered with the API's error payload.
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    code: &'static str,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, code: &'static str, message: String) -> ApiError {
+        ApiError {
+            status,
+            code,
+            message,
+        }
+    }
+
+    fn invalid(message: String) -> ApiError {
+        ApiError::new(400, "parameterInvalid", message)
+    }
+}
+
+type Params = HashMap<String, String>;
+
+/// The most sources a single request may name.
+const MAX_SOURCES: usize = 20;
+
+fn main() {
+    let config = match parse_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            eprintln!(
+                "usage: newsapi-fake-server <fixtures-dir> [--addr ADDR] [--key KEY]... [--max-results N]"
+            );
+            process::exit(2);
+        }
+    };
+
+    let server = match Server::http(&config.addr) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("error: cannot listen on {}: {}", config.addr, err);
+            process::exit(1);
+        }
+    };
+    eprintln!("Serving fake NewsAPI on http://{}/v2/", config.addr);
+
+    for request in server.incoming_requests() {
+        let (status, body) = match handle(&config, &request) {
+            Ok(body) => (200, body),
+            Err(err) => (
+                err.status,
+                json!({"status": "error", "code": err.code, "message": err.message}),
+            ),
+        };
+        let response = Response::from_string(body.to_string())
+            .with_status_code(status)
+            .with_header(
+                Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap(),
+            );
+        if let Err(err) = request.respond(response) {
+            eprintln!("error: failed to respond: {}", err);
+        }
+    }
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Config, String> {
+    let mut dir = None;
+    let mut addr = "127.0.0.1:8080".to_string();
+    let mut keys = Vec::new();
+    let mut max_results = None;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "--addr" => addr = value("--addr")?,
+            "--key" => keys.push(value("--key")?),
+            "--max-results" => {
+                let max = value("--max-results")?;
+                max_results = Some(
+                    max.parse()
+                        .map_err(|_| format!("invalid --max-results {}", max))?,
+                );
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ if dir.is_none() => dir = Some(arg),
+            _ => return Err(format!("unexpected argument {}", arg)),
+        }
+    }
+
+    let dir = dir.ok_or("missing fixtures directory")?;
+    Ok(Config {
+        addr,
+        keys,
+        max_results,
+        articles: load_fixture(&Path::new(&dir).join("articles.json"))?,
+        sources: load_fixture(&Path::new(&dir).join("sources.json"))?,
+    })
+}
+
+fn load_fixture(path: &Path) -> Result<Vec<Value>, String> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(format!("cannot read {}: {}", path.display(), err)),
+    };
+    serde_json::from_str(&contents)
+        .map_err(|err| format!("cannot parse {}: {}", path.display(), err))
+}
+
+fn handle(config: &Config, request: &Request) -> Result<Value, ApiError> {
+    let url = Url::parse(&format!("http://fake{}", request.url()))
+        .map_err(|err| ApiError::invalid(format!("Malformed request URL: {}", err)))?;
+    let params: Params = url.query_pairs().into_owned().collect();
+
+    check_key(config, request, &params)?;
+
+    match url.path().trim_end_matches('/') {
+        "/v2/top-headlines" => top_headlines(config, &params),
+        "/v2/everything" => everything(config, &params),
+        "/v2/top-headlines/sources" => sources(config, &params),
+        path => Err(ApiError::new(
+            404,
+            "routeNotFound",
+            format!("No endpoint at {}", path),
+        )),
+    }
+}
+
+fn check_key(config: &Config, request: &Request, params: &Params) -> Result<(), ApiError> {
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv(name))
+            .map(|header| header.value.as_str().to_string())
+    };
+    let key = header("X-Api-Key")
+        .or_else(|| {
+            header("Authorization").map(|value| value.trim_start_matches("Bearer ").to_string())
+        })
+        .or_else(|| params.get("apiKey").cloned())
+        .filter(|key| !key.is_empty());
+
+    match key {
+        None => Err(ApiError::new(
+            401,
+            "apiKeyMissing",
+            "Your API key is missing. Append this to the URL with the apiKey param, or use the x-api-key HTTP header.".to_string(),
+        )),
+        Some(key) if !config.keys.is_empty() && !config.keys.contains(&key) => Err(ApiError::new(
+            401,
+            "apiKeyInvalid",
+            "Your API key is invalid or incorrect. Check your key, or go to https://newsapi.org to create a free API key.".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+fn top_headlines(config: &Config, params: &Params) -> Result<Value, ApiError> {
+    let country: Option<Country> = parse_param(params, "country")?;
+    let category: Option<Category> = parse_param(params, "category")?;
+    let sources = list_param(params, "sources");
+    let query = params.get("q");
+
+    check_sources(config, &sources)?;
+    if !sources.is_empty() && (country.is_some() || category.is_some()) {
+        return Err(ApiError::invalid(
+            "You can't mix the sources parameter with the country or category parameters."
+                .to_string(),
+        ));
+    }
+    if sources.is_empty() && country.is_none() && category.is_none() && query.is_none() {
+        return Err(ApiError::new(
+            400,
+            "parametersMissing",
+            "Required parameters are missing. Please set any of the following parameters and try again: sources, q, country, category.".to_string(),
+        ));
+    }
+
+    let query = query.map(|query| Query::parse(query)).transpose()?;
+    let mut articles: Vec<&Value> = config
+        .articles
+        .iter()
+        .filter(|article| {
+            let source = source_of(config, article);
+            let source_field = |field: &str| source.and_then(|source| source[field].as_str());
+            country.map_or(true, |country| {
+                source_field("country") == Some(country.code())
+            }) && category.map_or(true, |category| {
+                source_field("category") == Some(category.code())
+            }) && (sources.is_empty() || sources.iter().any(|id| source_id(article) == Some(id)))
+        })
+        .filter(|article| {
+            query
+                .as_ref()
+                .map_or(true, |query| query.matches(article, &[]))
+        })
+        .collect();
+    sort_articles(&mut articles, SortBy::PublishedAt, None);
+
+    paginate(config, params, articles, 20)
+}
+
+fn everything(config: &Config, params: &Params) -> Result<Value, ApiError> {
+    let query = params.get("q");
+    let search_in = list_param(params, "searchIn")
+        .iter()
+        .map(|field| SearchIn::from_str(field).map_err(|err| ApiError::invalid(err.to_string())))
+        .collect::<Result<Vec<_>, _>>()?;
+    let sources = list_param(params, "sources");
+    let domains = list_param(params, "domains");
+    let exclude_domains = list_param(params, "excludeDomains");
+    let from = time_param(params, "from")?;
+    let to = time_param(params, "to")?;
+    let language: Option<Language> = parse_param(params, "language")?;
+    let sort_by: SortBy = parse_param(params, "sortBy")?.unwrap_or(SortBy::PublishedAt);
+
+    check_sources(config, &sources)?;
+    if query.is_none() && sources.is_empty() && domains.is_empty() {
+        return Err(ApiError::new(
+            400,
+            "parametersMissing",
+            "Required parameters are missing, the scope of your search is too broad. Please set any of the following required parameters and try again: q, sources, domains.".to_string(),
+        ));
+    }
+
+    let query = query.map(|query| Query::parse(query)).transpose()?;
+    let mut articles: Vec<&Value> = config
+        .articles
+        .iter()
+        .filter(|article| {
+            let host = article["url"]
+                .as_str()
+                .and_then(|url| Url::parse(url).ok())
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_default();
+            let on_domain =
+                |domain: &String| host == *domain || host.ends_with(&format!(".{}", domain));
+            let published = published_at(article);
+            let language_matches = language.map_or(true, |language| {
+                source_of(config, article).and_then(|source| source["language"].as_str())
+                    == Some(language.code())
+            });
+
+            (sources.is_empty() || sources.iter().any(|id| source_id(article) == Some(id)))
+                && (domains.is_empty() || domains.iter().any(on_domain))
+                && !exclude_domains.iter().any(on_domain)
+                && from.map_or(true, |from| published.is_some_and(|at| at >= from))
+                && to.map_or(true, |to| published.is_some_and(|at| at <= to))
+                && language_matches
+                && query
+                    .as_ref()
+                    .map_or(true, |query| query.matches(article, &search_in))
+        })
+        .collect();
+    sort_articles(&mut articles, sort_by, query.as_ref());
+
+    paginate(config, params, articles, 100)
+}
+
+fn sources(config: &Config, params: &Params) -> Result<Value, ApiError> {
+    let category: Option<Category> = parse_param(params, "category")?;
+    let language: Option<Language> = parse_param(params, "language")?;
+    let country: Option<Country> = parse_param(params, "country")?;
+
+    let sources: Vec<&Value> = config
+        .sources
+        .iter()
+        .filter(|source| {
+            let field = |name: &str| source[name].as_str();
+            category.map_or(true, |category| field("category") == Some(category.code()))
+                && language.map_or(true, |language| field("language") == Some(language.code()))
+                && country.map_or(true, |country| field("country") == Some(country.code()))
+        })
+        .collect();
+
+    Ok(json!({"status": "ok", "sources": sources}))
+}
+
+fn paginate(
+    config: &Config,
+    params: &Params,
+    articles: Vec<&Value>,
+    default_page_size: usize,
+) -> Result<Value, ApiError> {
+    let page_size: usize = parse_param(params, "pageSize")?.unwrap_or(default_page_size);
+    let page: usize = parse_param(params, "page")?.unwrap_or(1);
+    if page_size == 0 || page_size > 100 {
+        return Err(ApiError::invalid(format!(
+            "pageSize must be between 1 and 100, got {}.",
+            page_size
+        )));
+    }
+    if page == 0 {
+        return Err(ApiError::invalid("page must be 1 or higher.".to_string()));
+    }
+
+    let skip = (page - 1) * page_size;
+    if let Some(max_results) = config.max_results {
+        if skip >= max_results {
+            return Err(ApiError::new(
+                426,
+                "maximumResultsReached",
+                format!(
+                    "You have requested too many results. Developer accounts are limited to a max of {} results.",
+                    max_results
+                ),
+            ));
+        }
+    }
+
+    let total_results = articles.len();
+    let articles: Vec<&Value> = articles.into_iter().skip(skip).take(page_size).collect();
+    Ok(json!({"status": "ok", "totalResults": total_results, "articles": articles}))
+}
+
+/// Rejects more sources than one request may name, or any the fixtures do
+/// not list.
+fn check_sources(config: &Config, sources: &[String]) -> Result<(), ApiError> {
+    if sources.len() > MAX_SOURCES {
+        return Err(ApiError::new(
+            400,
+            "sourcesTooMany",
+            format!(
+                "You have requested too many sources in a single request. Try splitting the request into 2 smaller requests. A request may name at most {} sources.",
+                MAX_SOURCES
+            ),
+        ));
+    }
+    match sources.iter().find(|id| {
+        !config
+            .sources
+            .iter()
+            .any(|source| source["id"].as_str() == Some(id.as_str()))
+    }) {
+        Some(id) => Err(ApiError::new(
+            400,
+            "sourceDoesNotExist",
+            format!("You have requested a source which does not exist: {}.", id),
+        )),
+        None => Ok(()),
+    }
+}
+
+fn parse_param<T: FromStr>(params: &Params, name: &str) -> Result<Option<T>, ApiError> {
+    params
+        .get(name)
+        .map(|value| {
+            value.parse().map_err(|_| {
+                ApiError::invalid(format!(
+                    "The {} parameter has an invalid value: {}.",
+                    name, value
+                ))
+            })
+        })
+        .transpose()
+}
+
+fn list_param(params: &Params, name: &str) -> Vec<String> {
+    params
+        .get(name)
+        .map(|value| {
+            value
+                .split(',')
+                .map(|item| item.trim().to_string())
+                .filter(|item| !item.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses an ISO 8601 date or date and time, as `from` and `to` accept both.
+fn time_param(params: &Params, name: &str) -> Result<Option<DateTime<Utc>>, ApiError> {
+    let value = match params.get(name) {
+        Some(value) => value,
+        None => return Ok(None),
+    };
+    let parsed = DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .or_else(|_| {
+            chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                .map(|time| Utc.from_utc_datetime(&time))
+        })
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|date| {
+                Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("midnight exists"))
+            })
+        });
+    parsed.map(Some).map_err(|_| {
+        ApiError::invalid(format!(
+            "The {} parameter is not a valid ISO 8601 date: {}.",
+            name, value
+        ))
+    })
+}
+
+fn source_id(article: &Value) -> Option<&str> {
+    article["source"]["id"].as_str()
+}
+
+fn source_of<'a>(config: &'a Config, article: &Value) -> Option<&'a Value> {
+    let id = source_id(article)?;
+    config
+        .sources
+        .iter()
+        .find(|source| source["id"].as_str() == Some(id))
+}
+
+fn published_at(article: &Value) -> Option<DateTime<Utc>> {
+    article["publishedAt"]
+        .as_str()
+        .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+        .map(|at| at.with_timezone(&Utc))
+}
+
+/// Newest first, or by how often the query's terms occur for `relevancy`.
+/// Popularity is not in the fixtures, so it sorts like `publishedAt`.
+fn sort_articles(articles: &mut Vec<&Value>, sort_by: SortBy, query: Option<&Query>) {
+    articles.sort_by_key(|article| std::cmp::Reverse(published_at(article)));
+    if let (SortBy::Relevancy, Some(query)) = (sort_by, query) {
+        articles.sort_by_key(|article| std::cmp::Reverse(query.score(article)));
+    }
+}
+
+/// A parsed `q`: terms and quoted phrases, `+`/`-` prefixes, `AND`, `OR`,
+/// `NOT` and parentheses. Terms next to each other must all match, `NOT`
+/// and the prefixes bind tightest and `OR` loosest.
+enum Query {
+    Term(String),
+    Not(Box<Query>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+#[derive(Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Plus,
+    Minus,
+    Word(String),
+    Phrase(String),
+}
+
+impl Query {
+    fn parse(query: &str) -> Result<Query, ApiError> {
+        let tokens = Query::tokenize(query)?;
+        let mut parser = QueryParser {
+            tokens,
+            position: 0,
+        };
+        let query = parser.or()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some(Token::Close) => Err(query_error("has an unmatched `)`")),
+            Some(_) => Err(query_error("could not be parsed")),
+        }
+    }
+
+    fn tokenize(query: &str) -> Result<Vec<Token>, ApiError> {
+        let mut tokens = Vec::new();
+        let mut chars = query.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                c if c.is_whitespace() => {}
+                '(' => tokens.push(Token::Open),
+                ')' => tokens.push(Token::Close),
+                '+' => tokens.push(Token::Plus),
+                '-' => tokens.push(Token::Minus),
+                '"' => {
+                    let mut phrase = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some(c) => phrase.push(c),
+                            None => return Err(query_error("has an unterminated phrase")),
+                        }
+                    }
+                    tokens.push(Token::Phrase(phrase));
+                }
+                c => {
+                    let mut word = c.to_string();
+                    while let Some(&c) = chars.peek() {
+                        if c.is_whitespace() || matches!(c, '(' | ')' | '"') {
+                            break;
+                        }
+                        word.push(c);
+                        chars.next();
+                    }
+                    tokens.push(Token::Word(word));
+                }
+            }
+        }
+        Ok(tokens)
+    }
+
+    fn text(article: &Value, search_in: &[SearchIn]) -> String {
+        let fields: &[SearchIn] = if search_in.is_empty() {
+            &[SearchIn::Title, SearchIn::Description, SearchIn::Content]
+        } else {
+            search_in
+        };
+        fields
+            .iter()
+            .filter_map(|field| article[field.code()].as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+            .to_lowercase()
+    }
+
+    fn matches(&self, article: &Value, search_in: &[SearchIn]) -> bool {
+        self.matches_text(&Query::text(article, search_in))
+    }
+
+    fn matches_text(&self, text: &str) -> bool {
+        match self {
+            Query::Term(term) => text.contains(term.as_str()),
+            Query::Not(query) => !query.matches_text(text),
+            Query::And(queries) => queries.iter().all(|query| query.matches_text(text)),
+            Query::Or(queries) => queries.iter().any(|query| query.matches_text(text)),
+        }
+    }
+
+    /// How often the terms that should appear do appear.
+    fn score(&self, article: &Value) -> usize {
+        self.score_text(&Query::text(article, &[]))
+    }
+
+    fn score_text(&self, text: &str) -> usize {
+        match self {
+            Query::Term(term) => text.matches(term.as_str()).count(),
+            Query::Not(_) => 0,
+            Query::And(queries) | Query::Or(queries) => {
+                queries.iter().map(|query| query.score_text(text)).sum()
+            }
+        }
+    }
+}
+
+struct QueryParser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl QueryParser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_word(&self, word: &str) -> bool {
+        self.peek() == Some(&Token::Word(word.to_string()))
+    }
+
+    fn or(&mut self) -> Result<Query, ApiError> {
+        let mut queries = vec![self.and()?];
+        while self.is_word("OR") {
+            self.next();
+            queries.push(self.and()?);
+        }
+        Ok(if queries.len() == 1 {
+            queries.remove(0)
+        } else {
+            Query::Or(queries)
+        })
+    }
+
+    /// Operands joined by `AND`, or simply written next to each other.
+    fn and(&mut self) -> Result<Query, ApiError> {
+        let mut queries = vec![self.unary()?];
+        loop {
+            if self.is_word("AND") {
+                self.next();
+            } else if matches!(self.peek(), None | Some(Token::Close)) || self.is_word("OR") {
+                break;
+            }
+            queries.push(self.unary()?);
+        }
+        Ok(if queries.len() == 1 {
+            queries.remove(0)
+        } else {
+            Query::And(queries)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Query, ApiError> {
+        if self.is_word("NOT") {
+            self.next();
+            return Ok(Query::Not(Box::new(self.unary()?)));
+        }
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.next();
+                Ok(Query::Not(Box::new(self.primary()?)))
+            }
+            Some(Token::Plus) => {
+                self.next();
+                self.primary()
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Query, ApiError> {
+        match self.next() {
+            Some(Token::Open) => {
+                let query = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(query),
+                    _ => Err(query_error("has an unmatched `(`")),
+                }
+            }
+            Some(Token::Word(word)) if matches!(word.as_str(), "AND" | "OR" | "NOT") => Err(
+                query_error(&format!("has `{}` where a term was expected", word)),
+            ),
+            Some(Token::Word(term)) | Some(Token::Phrase(term)) if !term.trim().is_empty() => {
+                Ok(Query::Term(term.to_lowercase()))
+            }
+            Some(Token::Close) => Err(query_error("has an unmatched `)`")),
+            _ => Err(query_error("is missing a term")),
+        }
+    }
+}
+
+fn query_error(problem: &str) -> ApiError {
+    ApiError::invalid(format!("The q parameter {}.", problem))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(query: &str, text: &str) -> bool {
+        Query::parse(query).unwrap().matches_text(text)
+    }
+
+    #[test]
+    fn groups_bind_as_written() {
+        let query = "+(x OR y) AND NOT z";
+        assert!(matches(query, "x"));
+        assert!(matches(query, "y"));
+        assert!(!matches(query, "x z"));
+        assert!(!matches(query, "y z"));
+        assert!(!matches(query, "z"));
+    }
+
+    #[test]
+    fn adjacent_terms_must_all_match() {
+        assert!(matches(
+            "volvo \"electric car\" -diesel",
+            "volvo electric car"
+        ));
+        assert!(!matches(
+            "volvo \"electric car\" -diesel",
+            "volvo electric car diesel"
+        ));
+        assert!(!matches("volvo \"electric car\"", "volvo car electric"));
+        assert!(matches("a b OR c", "c"));
+        assert!(!matches("a b OR c", "a"));
+    }
+
+    fn config() -> Config {
+        Config {
+            addr: String::new(),
+            keys: Vec::new(),
+            max_results: None,
+            articles: Vec::new(),
+            sources: (1..=21)
+                .map(|i| json!({"id": format!("source-{}", i), "name": "Source"}))
+                .collect(),
+        }
+    }
+
+    fn params(pairs: &[(&str, String)]) -> Params {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect()
+    }
+
+    fn source_ids(count: usize) -> String {
+        (1..=count)
+            .map(|i| format!("source-{}", i))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    #[test]
+    fn requests_name_at_most_twenty_sources() {
+        let config = config();
+        for endpoint in [top_headlines, everything] {
+            assert!(endpoint(&config, &params(&[("sources", source_ids(20))])).is_ok());
+            let err = endpoint(&config, &params(&[("sources", source_ids(21))])).unwrap_err();
+            assert_eq!(err.code, "sourcesTooMany");
+        }
+    }
+
+    #[test]
+    fn unknown_sources_are_rejected() {
+        let config = config();
+        let sources = format!("{},missing", source_ids(2));
+        for endpoint in [top_headlines, everything] {
+            let err = endpoint(&config, &params(&[("sources", sources.clone())])).unwrap_err();
+            assert_eq!(err.code, "sourceDoesNotExist");
+            assert!(err.message.contains("missing"), "{}", err.message);
+        }
+    }
+
+    #[test]
+    fn malformed_queries_are_rejected() {
+        for query in ["(a OR b", "a)", "\"a", "a AND", "OR a", "()", ""] {
+            let err = Query::parse(query).err().expect(query);
+            assert_eq!(err.code, "parameterInvalid", "{}", query);
+        }
+    }
+}
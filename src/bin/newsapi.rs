@@ -0,0 +1,444 @@
+//! Command-line access to NewsAPI.
+//!
+//! The API key is taken from `--api-key`, the `NEWSAPI_KEY` environment
+//! variable or the `api_key` entry of the config file, in that order. The
+//! config file lives at `$XDG_CONFIG_HOME/newsapi/config.toml` (or
+//! `~/.config/newsapi/config.toml`) unless `--config` says otherwise, and may
+//! also set `base_url`.
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use newsapi::{
+    Article, Category, Country, EverythingRequest, Language, NewsAPI, SearchIn, SortBy, Source,
+    SourcesRequest, TopHeadlinesRequest,
+};
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process;
+
+#[derive(Parser)]
+#[command(
+    name = "newsapi",
+    version,
+    about = "Query NewsAPI from the command line"
+)]
+struct Cli {
+    /// API key, overriding NEWSAPI_KEY and the config file
+    #[arg(long, global = true)]
+    api_key: Option<String>,
+    /// Config file to read instead of the default one
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Send requests to this URL instead of https://newsapi.org/v2/
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+    #[arg(long, short, global = true, value_enum, default_value = "table")]
+    format: Format,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+    Ndjson,
+    Csv,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Top headlines, from /v2/top-headlines
+    Headlines(HeadlinesArgs),
+    /// Search every article, from /v2/everything
+    Search(SearchArgs),
+    /// Publishers, from /v2/top-headlines/sources
+    Sources(SourcesArgs),
+}
+
+#[derive(Args)]
+struct HeadlinesArgs {
+    #[arg(long)]
+    country: Option<Country>,
+    #[arg(long)]
+    category: Option<Category>,
+    /// Comma-separated source ids; cannot be combined with country or category
+    #[arg(long, value_delimiter = ',')]
+    sources: Vec<String>,
+    /// Keywords or phrase to search for
+    #[arg(long, short)]
+    q: Option<String>,
+    #[arg(long)]
+    page_size: Option<u32>,
+    #[arg(long)]
+    page: Option<u32>,
+}
+
+#[derive(Args)]
+struct SearchArgs {
+    /// Keywords or phrase to search for, in NewsAPI's search syntax
+    #[arg(long, short)]
+    q: Option<String>,
+    /// Comma-separated fields to search in: title, description, content
+    #[arg(long, value_delimiter = ',')]
+    search_in: Vec<SearchIn>,
+    /// Comma-separated source ids
+    #[arg(long, value_delimiter = ',')]
+    sources: Vec<String>,
+    /// Comma-separated domains to restrict the search to
+    #[arg(long, value_delimiter = ',')]
+    domains: Vec<String>,
+    /// Comma-separated domains to leave out
+    #[arg(long, value_delimiter = ',')]
+    exclude_domains: Vec<String>,
+    /// Oldest article, as a date (2022-02-01) or RFC 3339 time
+    #[arg(long, value_parser = parse_time)]
+    from: Option<DateTime<Utc>>,
+    /// Newest article, as a date (2022-02-01) or RFC 3339 time
+    #[arg(long, value_parser = parse_time)]
+    to: Option<DateTime<Utc>>,
+    #[arg(long)]
+    language: Option<Language>,
+    #[arg(long)]
+    sort_by: Option<SortBy>,
+    #[arg(long)]
+    page_size: Option<u32>,
+    #[arg(long)]
+    page: Option<u32>,
+}
+
+#[derive(Args)]
+struct SourcesArgs {
+    #[arg(long)]
+    category: Option<Category>,
+    #[arg(long)]
+    language: Option<Language>,
+    #[arg(long)]
+    country: Option<Country>,
+}
+
+#[derive(Deserialize, Default)]
+struct Config {
+    api_key: Option<String>,
+    base_url: Option<String>,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        eprintln!("error: {}", err);
+        let mut source = err.source();
+        while let Some(cause) = source {
+            eprintln!("  caused by: {}", cause);
+            source = cause.source();
+        }
+        process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let config = load_config(cli.config.as_ref())?;
+    let api_key = cli
+        .api_key
+        .or_else(|| env::var("NEWSAPI_KEY").ok())
+        .or(config.api_key)
+        .ok_or("no API key: pass --api-key, set NEWSAPI_KEY or add api_key to the config file")?;
+
+    let mut api = NewsAPI::new(&api_key);
+    if let Some(base_url) = cli.base_url.or(config.base_url) {
+        api.base_url(&base_url);
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    match cli.command {
+        Command::Headlines(args) => {
+            let mut request = TopHeadlinesRequest::new().sources(&str_refs(&args.sources));
+            if let Some(country) = args.country {
+                request = request.country(country);
+            }
+            if let Some(category) = args.category {
+                request = request.category(category);
+            }
+            if let Some(q) = &args.q {
+                request = request.query(q);
+            }
+            if let Some(page_size) = args.page_size {
+                request = request.page_size(page_size);
+            }
+            if let Some(page) = args.page {
+                request = request.page(page);
+            }
+            write_articles(&mut out, cli.format, api.fetch(&request)?.articles())?;
+        }
+        Command::Search(args) => {
+            let mut request = EverythingRequest::new()
+                .sources(&str_refs(&args.sources))
+                .domains(&str_refs(&args.domains))
+                .exclude_domains(&str_refs(&args.exclude_domains))
+                .search_in(args.search_in);
+            if let Some(q) = &args.q {
+                request = request.query(q);
+            }
+            if let Some(from) = args.from {
+                request = request.from(from);
+            }
+            if let Some(to) = args.to {
+                request = request.to(to);
+            }
+            if let Some(language) = args.language {
+                request = request.language(language);
+            }
+            if let Some(sort_by) = args.sort_by {
+                request = request.sort_by(sort_by);
+            }
+            if let Some(page_size) = args.page_size {
+                request = request.page_size(page_size);
+            }
+            if let Some(page) = args.page {
+                request = request.page(page);
+            }
+            write_articles(&mut out, cli.format, api.fetch(&request)?.articles())?;
+        }
+        Command::Sources(args) => {
+            let mut request = SourcesRequest::new();
+            if let Some(category) = args.category {
+                request = request.category(category);
+            }
+            if let Some(language) = args.language {
+                request = request.language(language);
+            }
+            if let Some(country) = args.country {
+                request = request.country(country);
+            }
+            write_sources(&mut out, cli.format, api.fetch(&request)?.sources())?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn str_refs(values: &[String]) -> Vec<&str> {
+    values.iter().map(String::as_str).collect()
+}
+
+fn config_path() -> Option<PathBuf> {
+    let dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))?;
+    Some(dir.join("newsapi").join("config.toml"))
+}
+
+/// Reads `path`, or the default config file if it exists.
+fn load_config(path: Option<&PathBuf>) -> Result<Config, Box<dyn Error>> {
+    let (path, required) = match path {
+        Some(path) => (path.clone(), true),
+        None => match config_path() {
+            Some(path) => (path, false),
+            None => return Ok(Config::default()),
+        },
+    };
+    match fs::read_to_string(&path) {
+        Ok(contents) => toml::from_str(&contents)
+            .map_err(|err| format!("invalid config file {}: {}", path.display(), err).into()),
+        Err(err) if !required && err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(err) => Err(format!("cannot read config file {}: {}", path.display(), err).into()),
+    }
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|date| {
+                Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("midnight exists"))
+            })
+        })
+        .map_err(|_| format!("`{}` is neither a date nor an RFC 3339 time", value))
+}
+
+const ARTICLE_COLUMNS: [&str; 8] = [
+    "published_at",
+    "source_id",
+    "source_name",
+    "author",
+    "title",
+    "description",
+    "url",
+    "url_to_image",
+];
+
+fn article_record(article: &Article) -> [String; 8] {
+    [
+        article.published_at().to_rfc3339(),
+        article.source().id().unwrap_or_default().to_string(),
+        article.source().name().to_string(),
+        article.author().unwrap_or_default().to_string(),
+        article.title().to_string(),
+        article.description().unwrap_or_default().to_string(),
+        article.url().to_string(),
+        article
+            .url_to_image()
+            .map(|url| url.to_string())
+            .unwrap_or_default(),
+    ]
+}
+
+fn write_articles<W: Write>(
+    out: &mut W,
+    format: Format,
+    articles: &[Article],
+) -> Result<(), Box<dyn Error>> {
+    match format {
+        Format::Table => {
+            let rows: Vec<Vec<String>> = articles
+                .iter()
+                .map(|article| {
+                    vec![
+                        article.published_at().format("%Y-%m-%d %H:%M").to_string(),
+                        article.source().name().to_string(),
+                        article.title().to_string(),
+                        article.url().to_string(),
+                    ]
+                })
+                .collect();
+            write_table(out, &["PUBLISHED", "SOURCE", "TITLE", "URL"], &rows)
+        }
+        Format::Json => write_json(out, articles),
+        Format::Ndjson => write_ndjson(out, articles),
+        Format::Csv => write_csv(out, &ARTICLE_COLUMNS, articles.iter().map(article_record)),
+    }
+}
+
+fn write_sources<W: Write>(
+    out: &mut W,
+    format: Format,
+    sources: &[Source],
+) -> Result<(), Box<dyn Error>> {
+    let record = |source: &Source| {
+        [
+            source.id().to_string(),
+            source.name().to_string(),
+            source.category().to_string(),
+            source.language().to_string(),
+            source.country().to_string(),
+            source.url().to_string(),
+            source.description().to_string(),
+        ]
+    };
+    match format {
+        Format::Table => {
+            let rows: Vec<Vec<String>> = sources
+                .iter()
+                .map(|source| record(source)[..6].to_vec())
+                .collect();
+            write_table(
+                out,
+                &["ID", "NAME", "CATEGORY", "LANGUAGE", "COUNTRY", "URL"],
+                &rows,
+            )
+        }
+        Format::Json => write_json(out, sources),
+        Format::Ndjson => write_ndjson(out, sources),
+        Format::Csv => write_csv(
+            out,
+            &[
+                "id",
+                "name",
+                "category",
+                "language",
+                "country",
+                "url",
+                "description",
+            ],
+            sources.iter().map(record),
+        ),
+    }
+}
+
+/// Longest a table cell may be before it is cut short.
+const MAX_CELL_WIDTH: usize = 80;
+
+fn write_table<W: Write>(
+    out: &mut W,
+    header: &[&str],
+    rows: &[Vec<String>],
+) -> Result<(), Box<dyn Error>> {
+    let cell = |value: &str| -> String {
+        let value = value.replace(['\n', '\r', '\t'], " ");
+        if value.chars().count() > MAX_CELL_WIDTH {
+            let cut: String = value.chars().take(MAX_CELL_WIDTH - 1).collect();
+            format!("{}…", cut)
+        } else {
+            value
+        }
+    };
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| row.iter().map(|value| cell(value)).collect())
+        .collect();
+    let widths: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, title)| {
+            rows.iter()
+                .map(|row| row[i].chars().count())
+                .chain(std::iter::once(title.len()))
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let header: Vec<String> = header.iter().map(|title| title.to_string()).collect();
+    for row in std::iter::once(&header).chain(rows.iter()) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| {
+                let padding = width - value.chars().count();
+                format!("{}{}", value, " ".repeat(padding))
+            })
+            .collect();
+        writeln!(out, "{}", line.join("  ").trim_end())?;
+    }
+    Ok(())
+}
+
+fn write_json<W: Write, T: serde::Serialize>(
+    out: &mut W,
+    items: &[T],
+) -> Result<(), Box<dyn Error>> {
+    serde_json::to_writer_pretty(&mut *out, items)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn write_ndjson<W: Write, T: serde::Serialize>(
+    out: &mut W,
+    items: &[T],
+) -> Result<(), Box<dyn Error>> {
+    for item in items {
+        serde_json::to_writer(&mut *out, item)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn write_csv<W: Write, R: AsRef<[String]>, I: Iterator<Item = R>>(
+    out: &mut W,
+    header: &[&str],
+    records: I,
+) -> Result<(), Box<dyn Error>> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(header)?;
+    for record in records {
+        writer.write_record(record.as_ref())?;
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use crate::transport::without_api_key;
+use crate::{file_error, Endpoint, FileKind, NewsApiError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+/// A successful response body and when it was received.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CacheEntry {
+    body: String,
+    stored_at: SystemTime,
+}
+
+impl CacheEntry {
+    pub fn new(body: &str) -> CacheEntry {
+        CacheEntry {
+            body: body.to_string(),
+            stored_at: SystemTime::now(),
+        }
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn stored_at(&self) -> SystemTime {
+        self.stored_at
+    }
+
+    fn age(&self) -> Duration {
+        self.stored_at.elapsed().unwrap_or_default()
+    }
+}
+
+/// Somewhere to keep cached responses, keyed by request URL.
+pub trait CacheStore: Send + Sync {
+    fn get(&self, key: &str) -> Result<Option<CacheEntry>, NewsApiError>;
+    fn put(&self, key: &str, entry: CacheEntry) -> Result<(), NewsApiError>;
+}
+
+/// Keeps up to `capacity` entries in memory, evicting the least recently
+/// used one first.
+pub struct MemoryCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+#[derive(Default)]
+struct LruState {
+    tick: u64,
+    entries: HashMap<String, (CacheEntry, u64)>,
+    recency: BTreeMap<u64, String>,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize) -> MemoryCache {
+        MemoryCache {
+            capacity,
+            state: Mutex::new(LruState::default()),
+        }
+    }
+}
+
+impl LruState {
+    fn touch(&mut self, key: &str) -> u64 {
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.get_mut(key) {
+            self.recency.remove(used);
+            *used = self.tick;
+        }
+        self.recency.insert(self.tick, key.to_string());
+        self.tick
+    }
+}
+
+impl CacheStore for MemoryCache {
+    fn get(&self, key: &str) -> Result<Option<CacheEntry>, NewsApiError> {
+        let mut state = self.state.lock().unwrap();
+        if !state.entries.contains_key(key) {
+            return Ok(None);
+        }
+        state.touch(key);
+        Ok(state.entries.get(key).map(|(entry, _)| entry.clone()))
+    }
+
+    fn put(&self, key: &str, entry: CacheEntry) -> Result<(), NewsApiError> {
+        let mut state = self.state.lock().unwrap();
+        let used = state.touch(key);
+        state.entries.insert(key.to_string(), (entry, used));
+        while state.entries.len() > self.capacity {
+            let oldest = match state.recency.keys().next() {
+                Some(oldest) => *oldest,
+                None => break,
+            };
+            if let Some(key) = state.recency.remove(&oldest) {
+                state.entries.remove(&key);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keeps one JSON file per entry in a directory, so the cache survives
+/// restarts and can be shared between processes.
+pub struct DiskCache {
+    dir: PathBuf,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DiskEntry {
+    key: String,
+    entry: CacheEntry,
+}
+
+impl DiskCache {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> DiskCache {
+        DiskCache { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(key)))
+    }
+}
+
+impl CacheStore for DiskCache {
+    fn get(&self, key: &str) -> Result<Option<CacheEntry>, NewsApiError> {
+        let path = self.path(key);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(file_error(FileKind::Cache, &path, err)),
+        };
+        let stored: DiskEntry = serde_json::from_str(&contents)
+            .map_err(|err| file_error(FileKind::Cache, &path, err))?;
+        if stored.key != key {
+            return Ok(None);
+        }
+        Ok(Some(stored.entry))
+    }
+
+    fn put(&self, key: &str, entry: CacheEntry) -> Result<(), NewsApiError> {
+        fs::create_dir_all(&self.dir).map_err(|err| file_error(FileKind::Cache, &self.dir, err))?;
+        let stored = DiskEntry {
+            key: key.to_string(),
+            entry,
+        };
+        let path = self.path(key);
+        fs::write(&path, serde_json::to_string(&stored)?)
+            .map_err(|err| file_error(FileKind::Cache, &path, err))?;
+        Ok(())
+    }
+}
+
+/// A hash that, unlike `DefaultHasher`, is stable across Rust releases, so
+/// file names stay valid between builds.
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Caches successful responses by request URL, with the API key left out
+/// of the key.
+///
+/// Entries younger than their endpoint's TTL are served without a request.
+/// With [`Cache::serve_stale_on_error`], an expired entry is served when the
+/// request fails instead of returning the error.
+pub struct Cache {
+    store: Box<dyn CacheStore>,
+    ttl: Duration,
+    endpoint_ttls: HashMap<Endpoint, Duration>,
+    serve_stale_on_error: bool,
+}
+
+impl Cache {
+    /// Caches responses in `store` for five minutes.
+    pub fn new<S: CacheStore + 'static>(store: S) -> Cache {
+        Cache {
+            store: Box::new(store),
+            ttl: Duration::from_secs(5 * 60),
+            endpoint_ttls: HashMap::new(),
+            serve_stale_on_error: false,
+        }
+    }
+
+    /// Caches up to `capacity` responses in memory.
+    pub fn memory(capacity: usize) -> Cache {
+        Cache::new(MemoryCache::new(capacity))
+    }
+
+    /// Caches responses as files in `dir`.
+    pub fn disk<P: Into<PathBuf>>(dir: P) -> Cache {
+        Cache::new(DiskCache::new(dir))
+    }
+
+    /// Sets the TTL of endpoints without one of their own.
+    pub fn ttl(mut self, ttl: Duration) -> Cache {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn endpoint_ttl(mut self, endpoint: Endpoint, ttl: Duration) -> Cache {
+        self.endpoint_ttls.insert(endpoint, ttl);
+        self
+    }
+
+    pub fn serve_stale_on_error(mut self, serve_stale_on_error: bool) -> Cache {
+        self.serve_stale_on_error = serve_stale_on_error;
+        self
+    }
+
+    /// The cached body for `url` if it is still fresh.
+    pub(crate) fn fresh(&self, endpoint: Endpoint, url: &str) -> Option<String> {
+        let ttl = self
+            .endpoint_ttls
+            .get(&endpoint)
+            .copied()
+            .unwrap_or(self.ttl);
+        let entry = self.store.get(&without_api_key(url)).ok()??;
+        if entry.age() >= ttl {
+            return None;
+        }
+        Some(entry.body)
+    }
+
+    /// The cached body for `url` whatever its age, if stale entries may be
+    /// served.
+    pub(crate) fn stale(&self, url: &str) -> Option<String> {
+        if !self.serve_stale_on_error {
+            return None;
+        }
+        Some(self.store.get(&without_api_key(url)).ok()??.body)
+    }
+
+    /// Stores `body` for `url`. A cache that cannot be written to should not
+    /// fail a request that succeeded, so errors are dropped.
+    pub(crate) fn store(&self, url: &str, body: &str) {
+        let _ = self.store.put(&without_api_key(url), CacheEntry::new(body));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::transport::without_api_key;
+#[cfg(feature = "async")]
+use crate::AsyncTransport;
+use crate::{file_error, FileKind, HttpRequest, HttpResponse, NewsApiError, Transport};
+#[cfg(feature = "async")]
+use futures::future::{BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// A transport that records requests and their responses to a file, or
+/// replays them from one without touching the network.
+///
+/// Recorded URLs have their `apiKey` parameter stripped, and request headers
+/// are not recorded, so cassettes never contain the key. When replaying,
+/// each recorded interaction for a URL answers once, in the order they were
+/// recorded, after which the last one keeps answering. A request with no
+/// recorded interaction fails with [`NewsApiError::CassetteMiss`]. A
+/// recording cassette only sends requests of its own kind, blocking or
+/// async, and fails others with [`NewsApiError::CassetteModeMismatch`].
+pub struct Cassette {
+    path: PathBuf,
+    mode: Mode,
+    state: Mutex<CassetteState>,
+}
+
+enum Mode {
+    Record(Arc<dyn Transport>),
+    #[cfg(feature = "async")]
+    RecordAsync(Arc<dyn AsyncTransport>),
+    Replay,
+}
+
+#[derive(Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    replayed: HashMap<String, usize>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Interaction {
+    url: String,
+    response: HttpResponse,
+}
+
+impl Cassette {
+    /// Sends requests through `transport`, saving every interaction to a new
+    /// cassette at `path`.
+    pub fn record<P: Into<PathBuf>, T: Transport + 'static>(path: P, transport: T) -> Cassette {
+        Cassette::new(path.into(), Mode::Record(Arc::new(transport)))
+    }
+
+    /// Sends async requests through `transport`, saving every interaction to
+    /// a new cassette at `path`.
+    #[cfg(feature = "async")]
+    pub fn record_async<P: Into<PathBuf>, T: AsyncTransport + 'static>(
+        path: P,
+        transport: T,
+    ) -> Cassette {
+        Cassette::new(path.into(), Mode::RecordAsync(Arc::new(transport)))
+    }
+
+    /// Answers requests from the cassette at `path`.
+    pub fn replay<P: AsRef<Path>>(path: P) -> Result<Cassette, NewsApiError> {
+        let path = path.as_ref();
+        let cassette = Cassette::new(path.to_path_buf(), Mode::Replay);
+        let contents =
+            fs::read_to_string(path).map_err(|err| file_error(FileKind::Cassette, path, err))?;
+        let interactions = serde_json::from_str(&contents)
+            .map_err(|err| file_error(FileKind::Cassette, path, err))?;
+        cassette.state.lock().unwrap().interactions = interactions;
+        Ok(cassette)
+    }
+
+    fn new(path: PathBuf, mode: Mode) -> Cassette {
+        Cassette {
+            path,
+            mode,
+            state: Mutex::new(CassetteState::default()),
+        }
+    }
+
+    fn save(&self, request: &HttpRequest, response: &HttpResponse) -> Result<(), NewsApiError> {
+        let mut state = self.state.lock().unwrap();
+        state.interactions.push(Interaction {
+            url: without_api_key(request.url()),
+            response: response.clone(),
+        });
+        fs::write(
+            &self.path,
+            serde_json::to_string_pretty(&state.interactions)?,
+        )
+        .map_err(|err| file_error(FileKind::Cassette, &self.path, err))?;
+        Ok(())
+    }
+
+    fn replay_response(&self, request: &HttpRequest) -> Result<HttpResponse, NewsApiError> {
+        let url = without_api_key(request.url());
+        let mut state = self.state.lock().unwrap();
+        let seen = state.replayed.get(&url).copied().unwrap_or(0);
+        let recorded: Vec<&Interaction> = state
+            .interactions
+            .iter()
+            .filter(|interaction| interaction.url == url)
+            .collect();
+        let response = match recorded.get(seen).or_else(|| recorded.last()) {
+            Some(interaction) => interaction.response.clone(),
+            None => return Err(NewsApiError::CassetteMiss(url)),
+        };
+        state.replayed.insert(url, seen + 1);
+        Ok(response)
+    }
+}
+
+impl Transport for Cassette {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, NewsApiError> {
+        match &self.mode {
+            Mode::Record(transport) => {
+                let response = transport.send(request)?;
+                self.save(request, &response)?;
+                Ok(response)
+            }
+            #[cfg(feature = "async")]
+            Mode::RecordAsync(_) => Err(NewsApiError::CassetteModeMismatch {
+                recorded: "async",
+                sent: "blocking",
+            }),
+            Mode::Replay => self.replay_response(request),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for Cassette {
+    fn send<'a>(
+        &'a self,
+        request: &'a HttpRequest,
+    ) -> BoxFuture<'a, Result<HttpResponse, NewsApiError>> {
+        async move {
+            match &self.mode {
+                Mode::RecordAsync(transport) => {
+                    let response = transport.send(request).await?;
+                    self.save(request, &response)?;
+                    Ok(response)
+                }
+                Mode::Record(_) => Err(NewsApiError::CassetteModeMismatch {
+                    recorded: "blocking",
+                    sent: "async",
+                }),
+                Mode::Replay => self.replay_response(request),
+            }
+        }
+        .boxed()
+    }
+}
@@ -1,33 +1,192 @@
-use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use url::Url;
 
+mod auth;
+mod cache;
+mod cassette;
+mod limit;
+mod pagination;
+mod params;
+mod parse;
+mod query;
+mod request;
+mod retry;
+#[cfg(feature = "async")]
+mod stream;
+mod transport;
+mod validate;
+mod watch;
+
+pub use auth::{ApiKey, KeyPlacement};
+pub use cache::{Cache, CacheEntry, CacheStore, DiskCache, MemoryCache};
+pub use cassette::Cassette;
+pub use limit::{DailyBudget, OverLimit, RateLimit};
+pub use pagination::{Articles, Pages};
+pub use params::{Category, Country, Endpoint, Language, SearchIn, SortBy};
+pub use query::{Query, MAX_QUERY_LENGTH};
+pub use request::{
+    AnyRequest, EverythingRequest, PagedRequest, Request, SourcesRequest, TopHeadlinesRequest,
+};
+pub use retry::RetryPolicy;
+#[cfg(feature = "async")]
+pub use stream::{ArticleStream, PageStream};
+#[cfg(feature = "async")]
+pub use transport::AsyncTransport;
+pub use transport::{HttpRequest, HttpResponse, MemoryTransport, Transport};
+pub use validate::MAX_SOURCES;
+pub use watch::Watch;
+
 const BASE_URL: &str = "https://newsapi.org/v2/";
 
+/// A point in time sent to or received from the API: a UTC
+/// [`chrono::DateTime`] with the `chrono` feature.
+#[cfg(feature = "chrono")]
+pub type Timestamp = chrono::DateTime<chrono::Utc>;
+
+/// A point in time sent to or received from the API: the RFC 3339 text, as
+/// in `2022-01-01T12:00:00Z`, without the `chrono` feature.
+#[cfg(not(feature = "chrono"))]
+pub type Timestamp = String;
+
 #[derive(thiserror::Error, Debug)]
 pub enum NewsApiError {
     #[error("Failed to fetch articles")]
     #[cfg(feature = "async")]
     AsyncRequestError(#[from] reqwest::Error),
     #[error("Failed to fetch articles")]
-    TransportError(#[from] ureq::Error),
+    TransportError(#[source] Box<ureq::Error>),
+    #[error("Failed to fetch articles: {message}")]
+    RedactedTransportError { message: String, retryable: bool },
     #[error("Failed to convert the response to string")]
     ConversionError(#[from] std::io::Error),
+    #[error("Failed to access the {kind} {}", .path.display())]
+    FileError {
+        kind: FileKind,
+        path: PathBuf,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
     #[error("Failed to parse the response")]
     ParseError(#[from] serde_json::Error),
     #[error("Failed to parse the URL")]
     UrlParseError(#[from] url::ParseError),
-    #[error("Error: {0}")]
-    UnknownError(&'static str),
-    #[error("Request failed: {0}")]
-    BadRequest(&'static str),
+    #[error("Your API key has been disabled: {0}")]
+    ApiKeyDisabled(String),
+    #[error("Your API key has no more requests available: {0}")]
+    ApiKeyExhausted(String),
+    #[error("Your API key is invalid: {0}")]
+    ApiKeyInvalid(String),
+    #[error("Your API key is missing: {0}")]
+    ApiKeyMissing(String),
+    #[error("Invalid request parameter: {0}")]
+    ParameterInvalid(String),
+    #[error("Required parameters are missing: {0}")]
+    ParametersMissing(String),
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+    #[error("Too many sources requested: {0}")]
+    SourcesTooMany(String),
+    #[error("Source does not exist: {0}")]
+    SourceDoesNotExist(String),
+    #[error("Maximum results reached: {0}")]
+    MaximumResultsReached(String),
+    #[error("Unexpected server error: {0}")]
+    UnexpectedError(String),
+    #[error("Request failed with HTTP status {status}")]
+    HttpStatus { status: u16, body: String },
+    #[error("Unknown error {code}: {message}")]
+    UnknownError { code: String, message: String },
+    #[error("Client-side rate limit reached, next request allowed in {0:?}")]
+    RateLimitExceeded(Duration),
+    #[error("Daily budget of {0} requests is spent")]
+    BudgetExhausted(u32),
+    #[error("Invalid request: {}", .0.join("; "))]
+    InvalidRequest(Vec<String>),
+    #[error("No recorded response for {0}")]
+    CassetteMiss(String),
+    #[error("The cassette records {recorded} requests and cannot send {sent} ones")]
+    CassetteModeMismatch {
+        recorded: &'static str,
+        sent: &'static str,
+    },
+    #[error("Invalid {kind} `{value}`, expected one of: {expected}")]
+    InvalidValue {
+        kind: &'static str,
+        value: String,
+        expected: String,
+    },
+}
+
+impl NewsApiError {
+    /// Whether the same request may succeed if sent again later: rate
+    /// limiting, server-side failures and I/O errors.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(feature = "async")]
+            Self::AsyncRequestError(err) => err.is_timeout() || err.is_connect() || err.is_body(),
+            Self::TransportError(err) => match &**err {
+                ureq::Error::Transport(transport) => matches!(
+                    transport.kind(),
+                    ureq::ErrorKind::Io | ureq::ErrorKind::ConnectionFailed | ureq::ErrorKind::Dns
+                ),
+                ureq::Error::Status(..) => false,
+            },
+            Self::RedactedTransportError { retryable, .. } => *retryable,
+            Self::ConversionError(_) => true,
+            Self::RateLimited(_) | Self::UnexpectedError(_) => true,
+            Self::HttpStatus { status, .. } => *status == 429 || *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+/// Which of the crate's own files a [`NewsApiError::FileError`] is about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileKind {
+    Cache,
+    Cassette,
+    WatchState,
+    Budget,
+}
+
+impl fmt::Display for FileKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cache => write!(f, "cache"),
+            Self::Cassette => write!(f, "cassette"),
+            Self::WatchState => write!(f, "watch state file"),
+            Self::Budget => write!(f, "budget file"),
+        }
+    }
+}
+
+/// A failure to read or write one of the crate's own files.
+pub(crate) fn file_error<E>(kind: FileKind, path: &Path, source: E) -> NewsApiError
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    NewsApiError::FileError {
+        kind,
+        path: path.to_path_buf(),
+        source: source.into(),
+    }
+}
+
+impl From<ureq::Error> for NewsApiError {
+    fn from(err: ureq::Error) -> Self {
+        NewsApiError::TransportError(Box::new(err))
+    }
 }
 
 #[allow(non_snake_case)]
-#[derive(Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct NewsAPIResponse {
-    status: String,
     totalResults: u32,
-    code: Option<String>,
     articles: Vec<Article>,
 }
 
@@ -35,16 +194,103 @@ impl NewsAPIResponse {
     pub fn articles(&self) -> &Vec<Article> {
         &self.articles
     }
+
+    pub fn into_articles(self) -> Vec<Article> {
+        self.articles
+    }
+
+    pub fn total_results(&self) -> u32 {
+        self.totalResults
+    }
 }
 
-#[allow(non_snake_case)]
+/// The response to an [`AnyRequest`]: articles or sources, depending on its
+/// endpoint.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum AnyResponse {
+    Articles(NewsAPIResponse),
+    Sources(SourcesResponse),
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SourcesResponse {
+    sources: Vec<Source>,
+}
+
+impl SourcesResponse {
+    pub fn sources(&self) -> &Vec<Source> {
+        &self.sources
+    }
+}
+
+/// A response body, tagged by its `status` field.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum ApiResponse<T> {
+    Ok(T),
+    Error(ApiErrorBody),
+}
+
+impl<T> ApiResponse<T> {
+    pub fn into_result(self) -> Result<T, NewsApiError> {
+        match self {
+            Self::Ok(body) => Ok(body),
+            Self::Error(err) => Err(err.into()),
+        }
+    }
+}
+
+/// The payload NewsAPI sends instead of results when a request fails.
 #[derive(Deserialize, Debug)]
+pub struct ApiErrorBody {
+    code: String,
+    #[serde(default)]
+    message: String,
+}
+
+impl ApiErrorBody {
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl From<ApiErrorBody> for NewsApiError {
+    fn from(body: ApiErrorBody) -> Self {
+        let ApiErrorBody { code, message } = body;
+        match code.as_str() {
+            "apiKeyDisabled" => NewsApiError::ApiKeyDisabled(message),
+            "apiKeyExhausted" => NewsApiError::ApiKeyExhausted(message),
+            "apiKeyInvalid" => NewsApiError::ApiKeyInvalid(message),
+            "apiKeyMissing" => NewsApiError::ApiKeyMissing(message),
+            "parameterInvalid" => NewsApiError::ParameterInvalid(message),
+            "parametersMissing" => NewsApiError::ParametersMissing(message),
+            "rateLimited" => NewsApiError::RateLimited(message),
+            "sourcesTooMany" => NewsApiError::SourcesTooMany(message),
+            "sourceDoesNotExist" => NewsApiError::SourceDoesNotExist(message),
+            "maximumResultsReached" => NewsApiError::MaximumResultsReached(message),
+            "unexpectedError" => NewsApiError::UnexpectedError(message),
+            _ => NewsApiError::UnknownError { code, message },
+        }
+    }
+}
+
+#[allow(non_snake_case)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Article {
     source: ArticleSource,
     title: String,
     author: Option<String>,
     description: Option<String>,
-    url: String,
+    url: Url,
+    #[serde(default, deserialize_with = "lenient_url")]
+    urlToImage: Option<Url>,
+    publishedAt: Timestamp,
+    content: Option<String>,
 }
 
 impl Article {
@@ -64,12 +310,32 @@ impl Article {
         self.author.as_deref()
     }
 
-    pub fn url(&self) -> &str {
+    pub fn url(&self) -> &Url {
         &self.url
     }
+
+    pub fn url_to_image(&self) -> Option<&Url> {
+        self.urlToImage.as_ref()
+    }
+
+    pub fn published_at(&self) -> &Timestamp {
+        &self.publishedAt
+    }
+
+    /// The first 200 characters of the article body, as returned by the API.
+    pub fn content(&self) -> Option<&str> {
+        self.content.as_deref()
+    }
 }
 
-#[derive(Deserialize, Debug)]
+/// Publishers regularly send empty or malformed image links, which should
+/// not fail the whole response.
+fn lenient_url<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<Url>, D::Error> {
+    let url: Option<String> = Option::deserialize(deserializer)?;
+    Ok(url.and_then(|url| Url::parse(&url).ok()))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ArticleSource {
     id: Option<String>,
     name: String,
@@ -85,101 +351,387 @@ impl ArticleSource {
     }
 }
 
-pub enum Endpoint {
-    TopHeadlines,
+/// A news publisher as listed by the `top-headlines/sources` endpoint.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Source {
+    id: String,
+    name: String,
+    description: String,
+    url: String,
+    category: String,
+    language: String,
+    country: String,
 }
 
-impl ToString for Endpoint {
-    fn to_string(&self) -> String {
-        match self {
-            Self::TopHeadlines => "top-headlines".to_string(),
-        }
+impl Source {
+    pub fn id(&self) -> &str {
+        &self.id
     }
-}
 
-pub enum Country {
-    US,
-    SE,
-}
+    pub fn name(&self) -> &str {
+        &self.name
+    }
 
-impl ToString for Country {
-    fn to_string(&self) -> String {
-        match self {
-            Self::US => "us".to_string(),
-            Self::SE => "se".to_string(),
-        }
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn country(&self) -> &str {
+        &self.country
     }
 }
 
+/// A NewsAPI client: the key, transports and settings shared by every
+/// request. The request itself is a [`TopHeadlinesRequest`],
+/// [`EverythingRequest`] or [`SourcesRequest`], so a single client can serve
+/// any number of queries at once.
 pub struct NewsAPI {
-    api_key: String,
-    endpoint: Endpoint,
-    country: Country,
+    api_key: ApiKey,
+    key_placement: KeyPlacement,
+    base_url: String,
+    retry: Option<RetryPolicy>,
+    cache: Option<Cache>,
+    rate_limit: Option<Mutex<RateLimit>>,
+    daily_budget: Option<Mutex<DailyBudget>>,
+    transport: Arc<dyn Transport>,
+    #[cfg(feature = "async")]
+    async_transport: Arc<dyn AsyncTransport>,
 }
 
 impl NewsAPI {
     pub fn new(api_key: &str) -> NewsAPI {
         NewsAPI {
-            api_key: api_key.to_string(),
-            endpoint: Endpoint::TopHeadlines,
-            country: Country::US,
+            api_key: ApiKey::new(api_key),
+            key_placement: KeyPlacement::default(),
+            base_url: BASE_URL.to_string(),
+            retry: None,
+            cache: None,
+            rate_limit: None,
+            daily_budget: None,
+            transport: Arc::new(ureq::Agent::new()),
+            #[cfg(feature = "async")]
+            async_transport: Arc::new(reqwest::Client::new()),
         }
     }
 
-    pub fn endpoint(&mut self, endpoint: Endpoint) -> &mut NewsAPI {
-        self.endpoint = endpoint;
+    /// Sends the API key as `placement` says, in an `X-Api-Key` header by
+    /// default.
+    pub fn key_placement(&mut self, placement: KeyPlacement) -> &mut NewsAPI {
+        self.key_placement = placement;
         self
     }
 
-    pub fn country(&mut self, country: Country) -> &mut NewsAPI {
-        self.country = country;
+    /// Sends requests to `base_url` instead of `https://newsapi.org/v2/`,
+    /// e.g. a caching proxy or a local mock server. Endpoint paths are
+    /// appended to its path, with or without a trailing slash.
+    pub fn base_url(&mut self, base_url: &str) -> &mut NewsAPI {
+        self.base_url = base_url.to_string();
         self
     }
 
-    pub fn prepare_url(&self) -> Result<String, NewsApiError> {
-        let mut url = Url::parse(BASE_URL)?;
-        url.path_segments_mut()
-            .unwrap()
-            .push(&self.endpoint.to_string());
+    /// Retries failed requests according to `policy`. Off by default.
+    pub fn retry(&mut self, policy: RetryPolicy) -> &mut NewsAPI {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Serves repeated requests from `cache` while its entries are fresh.
+    pub fn cache(&mut self, cache: Cache) -> &mut NewsAPI {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Spaces out requests according to `rate_limit`, retries included.
+    pub fn rate_limit(&mut self, rate_limit: RateLimit) -> &mut NewsAPI {
+        self.rate_limit = Some(Mutex::new(rate_limit));
+        self
+    }
+
+    /// Counts every request, retries included, against `daily_budget`.
+    pub fn daily_budget(&mut self, daily_budget: DailyBudget) -> &mut NewsAPI {
+        self.daily_budget = Some(Mutex::new(daily_budget));
+        self
+    }
+
+    /// Sends blocking requests through `agent`, so its connection pool,
+    /// proxy, timeout and TLS settings apply.
+    pub fn agent(&mut self, agent: ureq::Agent) -> &mut NewsAPI {
+        self.transport(agent)
+    }
+
+    /// Sends async requests through `client`, so its connection pool,
+    /// proxy, timeout and TLS settings apply.
+    #[cfg(feature = "async")]
+    pub fn client(&mut self, client: reqwest::Client) -> &mut NewsAPI {
+        self.async_transport(client)
+    }
 
-        let country = format!("country={}", self.country.to_string());
-        url.set_query(Some(&country));
+    /// Sends blocking requests through `transport` instead of ureq.
+    pub fn transport<T: Transport + 'static>(&mut self, transport: T) -> &mut NewsAPI {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Sends async requests through `transport` instead of reqwest.
+    #[cfg(feature = "async")]
+    pub fn async_transport<T: AsyncTransport + 'static>(&mut self, transport: T) -> &mut NewsAPI {
+        self.async_transport = Arc::new(transport);
+        self
+    }
+
+    /// The full URL `request` is sent to, after checking it with
+    /// [`Request::validate`].
+    pub fn prepare_url<R: Request>(&self, request: &R) -> Result<String, NewsApiError> {
+        request.validate()?;
+        let mut url = Url::parse(&self.base_url)?;
+        url.path_segments_mut()
+            .map_err(|_| url::ParseError::RelativeUrlWithCannotBeABaseBase)?
+            .pop_if_empty()
+            .extend(request.endpoint().to_string().split('/'));
+        url.query_pairs_mut().extend_pairs(request.query_pairs());
+        if url.query() == Some("") {
+            url.set_query(None);
+        }
 
         Ok(url.to_string())
     }
 
-    pub fn fetch(&self) -> Result<NewsAPIResponse, NewsApiError> {
-        let url = self.prepare_url()?;
-        let req = ureq::get(&url).set("Authorization", &self.api_key);
-        let json: NewsAPIResponse = req.call()?.into_json()?;
-        match json.status.as_str() {
-            "ok" => Ok(json),
-            _ => Err(map_response_err(json.code)),
+    pub fn fetch<R: Request>(&self, request: &R) -> Result<R::Response, NewsApiError> {
+        self.get(request.endpoint(), &self.prepare_url(request)?)
+    }
+
+    /// Walks every page of `request`, starting at its `page` (or the first
+    /// page) and requesting its `pageSize` articles at a time (or 100).
+    pub fn pages<R: PagedRequest>(&self, request: R) -> Pages<'_, R> {
+        Pages::new(self, request)
+    }
+
+    /// Yields the articles of every page of `request`.
+    pub fn articles<R: PagedRequest>(&self, request: R) -> Articles<'_, R> {
+        Articles::new(self.pages(request))
+    }
+
+    /// Polls `request` every `interval`, yielding only articles that have
+    /// not been seen before.
+    pub fn watch<R: Request<Response = NewsAPIResponse>>(
+        &self,
+        request: R,
+        interval: Duration,
+    ) -> Watch<'_, R> {
+        Watch::new(self, request, interval)
+    }
+
+    #[cfg(feature = "async")]
+    pub async fn fetch_async<R: Request>(&self, request: &R) -> Result<R::Response, NewsApiError> {
+        let url = self.prepare_url(request)?;
+        self.get_async(request.endpoint(), &url).await
+    }
+
+    /// Streams every page of `request`, like [`NewsAPI::pages`].
+    #[cfg(feature = "async")]
+    pub fn pages_async<R: PagedRequest>(&self, request: R) -> PageStream<'_, R> {
+        PageStream::new(self, request)
+    }
+
+    /// Streams the articles of every page of `request`, like
+    /// [`NewsAPI::articles`].
+    #[cfg(feature = "async")]
+    pub fn articles_async<R: PagedRequest>(&self, request: R) -> ArticleStream<'_, R> {
+        ArticleStream::new(self.pages_async(request))
+    }
+
+    fn request(&self, url: &str) -> Result<HttpRequest, NewsApiError> {
+        let key = self.api_key.expose();
+        let request = match self.key_placement {
+            KeyPlacement::XApiKeyHeader => HttpRequest::new(url).header("X-Api-Key", key),
+            KeyPlacement::BearerHeader => {
+                HttpRequest::new(url).header("Authorization", &format!("Bearer {}", key))
+            }
+            KeyPlacement::QueryParameter => {
+                let mut url = Url::parse(url)?;
+                url.query_pairs_mut().append_pair("apiKey", key);
+                HttpRequest::new(url.as_str())
+            }
+        };
+        Ok(request)
+    }
+
+    /// ureq and reqwest errors name the URL that failed, which holds the key
+    /// when it is sent as a query parameter. Such errors are reduced to their
+    /// message, with the key masked.
+    fn redact(&self, err: NewsApiError) -> NewsApiError {
+        if self.key_placement != KeyPlacement::QueryParameter {
+            return err;
+        }
+        let message = match &err {
+            NewsApiError::TransportError(source) => source.to_string(),
+            #[cfg(feature = "async")]
+            NewsApiError::AsyncRequestError(source) => source.to_string(),
+            _ => return err,
+        };
+        let key = self.api_key.expose();
+        let encoded_key: String = url::form_urlencoded::byte_serialize(key.as_bytes()).collect();
+        NewsApiError::RedactedTransportError {
+            message: message.replace(&encoded_key, "***").replace(key, "***"),
+            retryable: err.is_retryable(),
+        }
+    }
+
+    fn get<T: DeserializeOwned>(&self, endpoint: Endpoint, url: &str) -> Result<T, NewsApiError> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return parse_response(&self.send_with_retry(url)?),
+        };
+        if let Some(body) = cache.fresh(endpoint, url) {
+            return parse_body(&body);
         }
+        self.send_with_retry(url)
+            .and_then(|response| {
+                let json = parse_response(&response)?;
+                cache.store(url, response.body());
+                Ok(json)
+            })
+            .or_else(|err| parse_body(&cache.stale(url).ok_or(err)?))
     }
 
     #[cfg(feature = "async")]
-    pub async fn fetch_async(&self) -> Result<NewsAPIResponse, NewsApiError> {
-        let url = self.prepare_url()?;
-        let client = reqwest::Client::new();
-        let req = client
-            .request(reqwest::Method::GET, &url)
-            .header("Authorization", &self.api_key)
-            .build()?;
-        let json: NewsAPIResponse = client.execute(req).await?.json().await?;
-        match json.status.as_str() {
-            "ok" => Ok(json),
-            _ => Err(map_response_err(json.code)),
-        }
-    }
-}
-
-fn map_response_err(code: Option<String>) -> NewsApiError {
-    match code {
-        Some(code) => match code.as_str() {
-            "apiKeyDisabled" => NewsApiError::BadRequest("Your API key has been disabled"),
-            _ => NewsApiError::UnknownError("Unknown error"),
+    async fn get_async<T: DeserializeOwned>(
+        &self,
+        endpoint: Endpoint,
+        url: &str,
+    ) -> Result<T, NewsApiError> {
+        let cache = match &self.cache {
+            Some(cache) => cache,
+            None => return parse_response(&self.send_with_retry_async(url).await?),
+        };
+        if let Some(body) = cache.fresh(endpoint, url) {
+            return parse_body(&body);
+        }
+        self.send_with_retry_async(url)
+            .await
+            .and_then(|response| {
+                let json = parse_response(&response)?;
+                cache.store(url, response.body());
+                Ok(json)
+            })
+            .or_else(|err| parse_body(&cache.stale(url).ok_or(err)?))
+    }
+
+    fn send_with_retry(&self, url: &str) -> Result<HttpResponse, NewsApiError> {
+        let request = self.request(url)?;
+        let mut attempt = 1;
+        loop {
+            let result = self.send(&request);
+            match self.retry_delay(attempt, &result) {
+                Some(delay) => std::thread::sleep(delay),
+                None => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    #[cfg(feature = "async")]
+    async fn send_with_retry_async(&self, url: &str) -> Result<HttpResponse, NewsApiError> {
+        let request = self.request(url)?;
+        let mut attempt = 1;
+        loop {
+            let result = self.send_async(&request).await;
+            match self.retry_delay(attempt, &result) {
+                Some(delay) => futures_timer::Delay::new(delay).await,
+                None => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, NewsApiError> {
+        while let Some(delay) = self.acquire()? {
+            std::thread::sleep(delay);
+        }
+        self.transport.send(request).map_err(|err| self.redact(err))
+    }
+
+    #[cfg(feature = "async")]
+    async fn send_async(&self, request: &HttpRequest) -> Result<HttpResponse, NewsApiError> {
+        while let Some(delay) = self.acquire()? {
+            futures_timer::Delay::new(delay).await;
+        }
+        self.async_transport
+            .send(request)
+            .await
+            .map_err(|err| self.redact(err))
+    }
+
+    /// Reserves room for one request under the rate limit and daily budget,
+    /// or says how long to wait before asking again.
+    fn acquire(&self) -> Result<Option<Duration>, NewsApiError> {
+        if let Some(rate_limit) = &self.rate_limit {
+            if let Some(delay) = rate_limit.lock().unwrap().acquire()? {
+                return Ok(Some(delay));
+            }
+        }
+        match &self.daily_budget {
+            Some(daily_budget) => daily_budget.lock().unwrap().acquire(),
+            None => Ok(None),
+        }
+    }
+
+    /// How long to wait before retrying after `attempt` ended in `result`, or
+    /// `None` if `result` is final.
+    fn retry_delay(
+        &self,
+        attempt: u32,
+        result: &Result<HttpResponse, NewsApiError>,
+    ) -> Option<Duration> {
+        let policy = self.retry.as_ref()?;
+        match result {
+            Ok(response) if response.is_success() => None,
+            Ok(response) => {
+                let err = status_err(response.status(), response.body());
+                if err.is_retryable() {
+                    policy.delay(attempt, retry::retry_after(response))
+                } else {
+                    None
+                }
+            }
+            Err(err) if err.is_retryable() => policy.delay(attempt, None),
+            Err(_) => None,
+        }
+    }
+}
+
+fn parse_response<T: DeserializeOwned>(response: &HttpResponse) -> Result<T, NewsApiError> {
+    if !response.is_success() {
+        return Err(status_err(response.status(), response.body()));
+    }
+    parse_body(response.body())
+}
+
+fn parse_body<T: DeserializeOwned>(body: &str) -> Result<T, NewsApiError> {
+    let json: ApiResponse<T> = serde_json::from_str(body)?;
+    json.into_result()
+}
+
+/// Turns the body of a non-2xx response into the API error it describes,
+/// falling back to the bare HTTP status when the body is not a NewsAPI payload.
+fn status_err(status: u16, body: &str) -> NewsApiError {
+    match serde_json::from_str::<ApiErrorBody>(body) {
+        Ok(err) => err.into(),
+        Err(_) => NewsApiError::HttpStatus {
+            status,
+            body: body.to_string(),
         },
-        None => NewsApiError::UnknownError("Unknown Error"),
     }
 }
@@ -0,0 +1,241 @@
+use crate::{file_error, FileKind, NewsApiError};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// How long a budget lock may be held before it is taken to be abandoned.
+const STALE_LOCK: Duration = Duration::from_secs(10);
+
+/// What to do with a request that would go over a limit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OverLimit {
+    /// Block until the limit allows the request.
+    Wait,
+    /// Return an error without sending the request.
+    Fail,
+}
+
+/// A token bucket allowing bursts of up to `requests` requests, refilled at
+/// `requests` per `per`. Waits when empty unless told otherwise.
+///
+/// A `requests` of zero denies every request: as no token ever arrives,
+/// [`NewsApiError::RateLimitExceeded`] is returned instead of waiting.
+#[derive(Debug)]
+pub struct RateLimit {
+    capacity: f64,
+    tokens_per_second: f64,
+    tokens: f64,
+    refilled_at: Instant,
+    on_limit: OverLimit,
+}
+
+impl RateLimit {
+    pub fn new(requests: u32, per: Duration) -> RateLimit {
+        let tokens_per_second = if requests == 0 {
+            0.0
+        } else {
+            requests as f64 / per.as_secs_f64()
+        };
+        RateLimit {
+            capacity: requests as f64,
+            tokens_per_second,
+            tokens: requests as f64,
+            refilled_at: Instant::now(),
+            on_limit: OverLimit::Wait,
+        }
+    }
+
+    pub fn on_limit(mut self, on_limit: OverLimit) -> RateLimit {
+        self.on_limit = on_limit;
+        self
+    }
+
+    /// Takes a token, or says how long until one is available.
+    pub(crate) fn acquire(&mut self) -> Result<Option<Duration>, NewsApiError> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.tokens_per_second).min(self.capacity);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(None);
+        }
+
+        let wait = self.until_next_token();
+        match self.on_limit {
+            OverLimit::Wait if self.tokens_per_second > 0.0 => Ok(Some(wait)),
+            _ => Err(NewsApiError::RateLimitExceeded(wait)),
+        }
+    }
+
+    /// The time until the bucket holds a whole token, `Duration::MAX` if
+    /// that is longer than a `Duration` can hold or never happens.
+    fn until_next_token(&self) -> Duration {
+        let seconds = (1.0 - self.tokens) / self.tokens_per_second;
+        if seconds < u64::MAX as f64 {
+            Duration::from_secs_f64(seconds)
+        } else {
+            Duration::MAX
+        }
+    }
+}
+
+/// A cap on the number of requests sent per UTC day, matching how NewsAPI
+/// counts a key's daily quota. Fails once spent unless told otherwise.
+///
+/// With [`DailyBudget::persist`] the count is read from and written to a
+/// file on every request, so it survives restarts and is shared by every
+/// process pointed at the same file. Each update holds a lock file next to
+/// it, `<path>.lock`, and replaces the file in one rename, so processes
+/// neither lose each other's requests nor read a half-written count.
+#[derive(Debug)]
+pub struct DailyBudget {
+    limit: u32,
+    path: Option<PathBuf>,
+    usage: Usage,
+    on_limit: OverLimit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Usage {
+    /// Days since the Unix epoch, which start at midnight UTC.
+    day: u64,
+    count: u32,
+}
+
+impl DailyBudget {
+    pub fn new(limit: u32) -> DailyBudget {
+        DailyBudget {
+            limit,
+            path: None,
+            usage: Usage::default(),
+            on_limit: OverLimit::Fail,
+        }
+    }
+
+    /// Keeps the request count for the current day in the file at `path`.
+    pub fn persist<P: Into<PathBuf>>(mut self, path: P) -> DailyBudget {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn on_limit(mut self, on_limit: OverLimit) -> DailyBudget {
+        self.on_limit = on_limit;
+        self
+    }
+
+    /// Counts a request against today's budget, or says how long until the
+    /// budget resets.
+    pub(crate) fn acquire(&mut self) -> Result<Option<Duration>, NewsApiError> {
+        let _lock = match &self.path {
+            Some(path) => {
+                let lock = FileLock::acquire(path)?;
+                self.usage = read_usage(path)?;
+                Some(lock)
+            }
+            None => None,
+        };
+
+        let since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let today = since_epoch.as_secs() / SECONDS_PER_DAY;
+        if self.usage.day != today {
+            self.usage = Usage {
+                day: today,
+                count: 0,
+            };
+        }
+
+        if self.usage.count >= self.limit {
+            return match self.on_limit {
+                OverLimit::Wait => {
+                    let into_day = since_epoch.as_secs() % SECONDS_PER_DAY;
+                    Ok(Some(Duration::from_secs(SECONDS_PER_DAY - into_day)))
+                }
+                OverLimit::Fail => Err(NewsApiError::BudgetExhausted(self.limit)),
+            };
+        }
+
+        self.usage.count += 1;
+        if let Some(path) = &self.path {
+            write_usage(path, &self.usage)?;
+        }
+        Ok(None)
+    }
+}
+
+fn read_usage(path: &Path) -> Result<Usage, NewsApiError> {
+    match fs::read_to_string(path) {
+        Ok(contents) => {
+            serde_json::from_str(&contents).map_err(|err| file_error(FileKind::Budget, path, err))
+        }
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(Usage::default()),
+        Err(err) => Err(file_error(FileKind::Budget, path, err)),
+    }
+}
+
+/// Writes `usage` to a temporary file and renames it over `path`, so readers
+/// see either the old count or the new one.
+fn write_usage(path: &Path, usage: &Usage) -> Result<(), NewsApiError> {
+    let temporary = with_suffix(path, "tmp");
+    fs::write(&temporary, serde_json::to_string(usage)?)
+        .map_err(|err| file_error(FileKind::Budget, &temporary, err))?;
+    fs::rename(&temporary, path).map_err(|err| file_error(FileKind::Budget, path, err))
+}
+
+/// A lock file taken by creating it, which only succeeds for one process at
+/// a time, and released by removing it when dropped.
+struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// Locks `target`, waiting for the process holding the lock, if any. A
+    /// lock older than [`STALE_LOCK`] was left behind by a process that died
+    /// holding it and is taken over.
+    fn acquire(target: &Path) -> Result<FileLock, NewsApiError> {
+        let path = with_suffix(target, "lock");
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(FileLock { path }),
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                    if is_stale(&path) {
+                        let _ = fs::remove_file(&path);
+                    } else {
+                        thread::sleep(Duration::from_millis(5));
+                    }
+                }
+                Err(err) => return Err(file_error(FileKind::Budget, &path, err)),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn is_stale(lock: &Path) -> bool {
+    let age = fs::metadata(lock)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok());
+    matches!(age, Some(age) if age > STALE_LOCK)
+}
+
+/// `path` with `.suffix` appended to its file name.
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
@@ -0,0 +1,169 @@
+use crate::{Article, NewsAPI, NewsAPIResponse, NewsApiError, PagedRequest};
+use std::vec;
+
+/// The largest `pageSize` the API accepts.
+pub(crate) const MAX_PAGE_SIZE: u32 = 100;
+
+/// Which page of a request to ask for next, and whether to ask at all.
+/// Shared by the blocking iterators and the async streams.
+pub(crate) struct Cursor {
+    page: u32,
+    page_size: u32,
+    max_pages: Option<u32>,
+    requested_pages: u32,
+    done: bool,
+}
+
+impl Cursor {
+    pub(crate) fn new<R: PagedRequest>(request: &R) -> Cursor {
+        let (page, page_size) = request.paging();
+        Cursor {
+            page: page.unwrap_or(1),
+            page_size: page_size.unwrap_or(MAX_PAGE_SIZE),
+            max_pages: None,
+            requested_pages: 0,
+            done: false,
+        }
+    }
+
+    pub(crate) fn max_pages(&mut self, max_pages: u32) {
+        self.max_pages = Some(max_pages);
+    }
+
+    pub(crate) fn can_request(&self) -> bool {
+        !self.done && self.max_pages != Some(self.requested_pages)
+    }
+
+    /// `request` with the paging of the next page, which is then counted as
+    /// requested.
+    pub(crate) fn next_request<R: PagedRequest>(&mut self, request: &R) -> R {
+        let request = request.with_paging(self.page, self.page_size);
+        self.page += 1;
+        self.requested_pages += 1;
+        request
+    }
+
+    /// Takes in the response to the page requested last. Pages before the
+    /// first one requested count as seen, so a walk that starts part way
+    /// through stops at the same last page as one that starts at page 1.
+    pub(crate) fn complete(&mut self, response: &NewsAPIResponse) {
+        let last_page = self.page - 1;
+        let seen = u64::from(last_page) * u64::from(self.page_size);
+        if response.articles().is_empty() || seen >= u64::from(response.total_results()) {
+            self.done = true;
+        }
+    }
+
+    /// Requests no more pages.
+    pub(crate) fn stop(&mut self) {
+        self.done = true;
+    }
+}
+
+/// Blocking iterator over the pages of a request, created by [`NewsAPI::pages`].
+///
+/// Iteration stops once the page holding the last of `totalResults` has been
+/// fetched, when a page comes back empty, when the plan's result ceiling is
+/// hit (`maximumResultsReached`), or after the first error.
+pub struct Pages<'a, R> {
+    api: &'a NewsAPI,
+    request: R,
+    cursor: Cursor,
+}
+
+impl<'a, R: PagedRequest> Pages<'a, R> {
+    pub(crate) fn new(api: &'a NewsAPI, request: R) -> Pages<'a, R> {
+        Pages {
+            api,
+            cursor: Cursor::new(&request),
+            request,
+        }
+    }
+
+    /// Stops after `max_pages` requests, whatever `totalResults` says.
+    pub fn max_pages(mut self, max_pages: u32) -> Pages<'a, R> {
+        self.cursor.max_pages(max_pages);
+        self
+    }
+}
+
+impl<'a, R: PagedRequest> Iterator for Pages<'a, R> {
+    type Item = Result<NewsAPIResponse, NewsApiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.cursor.can_request() {
+            return None;
+        }
+
+        let request = self.cursor.next_request(&self.request);
+        match self.api.fetch(&request) {
+            Ok(response) => {
+                self.cursor.complete(&response);
+                Some(Ok(response))
+            }
+            Err(NewsApiError::MaximumResultsReached(_)) => {
+                self.cursor.stop();
+                None
+            }
+            Err(err) => {
+                self.cursor.stop();
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Blocking iterator over the articles of every page of a request, created by
+/// [`NewsAPI::articles`].
+pub struct Articles<'a, R> {
+    pages: Pages<'a, R>,
+    current: vec::IntoIter<Article>,
+    max_articles: Option<usize>,
+    yielded: usize,
+}
+
+impl<'a, R: PagedRequest> Articles<'a, R> {
+    pub(crate) fn new(pages: Pages<'a, R>) -> Articles<'a, R> {
+        Articles {
+            pages,
+            current: Vec::new().into_iter(),
+            max_articles: None,
+            yielded: 0,
+        }
+    }
+
+    /// Stops after `max_pages` requests.
+    pub fn max_pages(mut self, max_pages: u32) -> Articles<'a, R> {
+        self.pages = self.pages.max_pages(max_pages);
+        self
+    }
+
+    /// Stops after yielding `max_articles` articles, without requesting
+    /// pages that are not needed to reach it.
+    pub fn max_articles(mut self, max_articles: usize) -> Articles<'a, R> {
+        self.max_articles = Some(max_articles);
+        self
+    }
+}
+
+impl<'a, R: PagedRequest> Iterator for Articles<'a, R> {
+    type Item = Result<Article, NewsApiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.max_articles == Some(self.yielded) {
+            return None;
+        }
+
+        loop {
+            if let Some(article) = self.current.next() {
+                self.yielded += 1;
+                return Some(Ok(article));
+            }
+
+            match self.pages.next()? {
+                Ok(response) => self.current = response.into_articles().into_iter(),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use crate::NewsApiError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Declares a parameter enum whose variants map one-to-one onto the codes
+/// NewsAPI uses on the wire, together with `Display`, `FromStr` and serde
+/// impls that go through those codes.
+macro_rules! api_codes {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident ($kind:literal) {
+            $($variant:ident => $code:literal,)+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)+
+        }
+
+        impl $name {
+            /// Every value accepted by the API.
+            pub const ALL: &'static [$name] = &[$($name::$variant,)+];
+
+            /// The code sent to and received from the API.
+            pub fn code(&self) -> &'static str {
+                match self {
+                    $($name::$variant => $code,)+
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.code())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = NewsApiError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                $name::ALL
+                    .iter()
+                    .find(|value| value.code().eq_ignore_ascii_case(s))
+                    .copied()
+                    .ok_or_else(|| NewsApiError::InvalidValue {
+                        kind: $kind,
+                        value: s.to_string(),
+                        expected: $name::ALL
+                            .iter()
+                            .map(|value| value.code())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    })
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.code())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let code = String::deserialize(deserializer)?;
+                code.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    TopHeadlines,
+    Everything,
+    Sources,
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TopHeadlines => write!(f, "top-headlines"),
+            Self::Everything => write!(f, "everything"),
+            Self::Sources => write!(f, "top-headlines/sources"),
+        }
+    }
+}
+
+api_codes! {
+    /// The countries `top-headlines` and `top-headlines/sources` can be filtered by.
+    pub enum Country ("country") {
+        AE => "ae",
+        AR => "ar",
+        AT => "at",
+        AU => "au",
+        BE => "be",
+        BG => "bg",
+        BR => "br",
+        CA => "ca",
+        CH => "ch",
+        CN => "cn",
+        CO => "co",
+        CU => "cu",
+        CZ => "cz",
+        DE => "de",
+        EG => "eg",
+        FR => "fr",
+        GB => "gb",
+        GR => "gr",
+        HK => "hk",
+        HU => "hu",
+        ID => "id",
+        IE => "ie",
+        IL => "il",
+        IN => "in",
+        IT => "it",
+        JP => "jp",
+        KR => "kr",
+        LT => "lt",
+        LV => "lv",
+        MA => "ma",
+        MX => "mx",
+        MY => "my",
+        NG => "ng",
+        NL => "nl",
+        NO => "no",
+        NZ => "nz",
+        PH => "ph",
+        PL => "pl",
+        PT => "pt",
+        RO => "ro",
+        RS => "rs",
+        RU => "ru",
+        SA => "sa",
+        SE => "se",
+        SG => "sg",
+        SI => "si",
+        SK => "sk",
+        TH => "th",
+        TR => "tr",
+        TW => "tw",
+        UA => "ua",
+        US => "us",
+        VE => "ve",
+        ZA => "za",
+    }
+}
+
+api_codes! {
+    /// The languages `everything` and `top-headlines/sources` can be filtered by.
+    pub enum Language ("language") {
+        AR => "ar",
+        DE => "de",
+        EN => "en",
+        ES => "es",
+        FR => "fr",
+        HE => "he",
+        IT => "it",
+        NL => "nl",
+        NO => "no",
+        PT => "pt",
+        RU => "ru",
+        SV => "sv",
+        UD => "ud",
+        ZH => "zh",
+    }
+}
+
+api_codes! {
+    pub enum Category ("category") {
+        Business => "business",
+        Entertainment => "entertainment",
+        General => "general",
+        Health => "health",
+        Science => "science",
+        Sports => "sports",
+        Technology => "technology",
+    }
+}
+
+api_codes! {
+    /// The fields the `everything` endpoint can restrict `q` to.
+    pub enum SearchIn ("searchIn") {
+        Title => "title",
+        Description => "description",
+        Content => "content",
+    }
+}
+
+api_codes! {
+    pub enum SortBy ("sortBy") {
+        Relevancy => "relevancy",
+        Popularity => "popularity",
+        PublishedAt => "publishedAt",
+    }
+}
@@ -0,0 +1,129 @@
+use crate::validate::Violations;
+use crate::{NewsApiError, Timestamp};
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use std::str::FromStr;
+
+/// The parameters of a query string, taken one by one as a request is built
+/// from them. Values that do not parse and parameters left over at the end
+/// are collected as violations.
+pub(crate) struct Params {
+    pairs: Vec<(String, String)>,
+    violations: Violations,
+}
+
+impl Params {
+    /// Splits `query`, with or without its leading `?`. The API key is not
+    /// part of a request, so an `apiKey` parameter is dropped.
+    pub(crate) fn new(query: &str) -> Params {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        let mut params = Params {
+            pairs: Vec::new(),
+            violations: Violations::default(),
+        };
+        for (name, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            if name == "apiKey" {
+                continue;
+            }
+            if params.pairs.iter().any(|(seen, _)| *seen == name) {
+                params
+                    .violations
+                    .push(format!("parameter `{}` is given more than once", name));
+                continue;
+            }
+            params.pairs.push((name.into_owned(), value.into_owned()));
+        }
+        params
+    }
+
+    pub(crate) fn string(&mut self, name: &str) -> Option<String> {
+        let index = self.pairs.iter().position(|(seen, _)| seen == name)?;
+        Some(self.pairs.remove(index).1)
+    }
+
+    pub(crate) fn value<T: FromStr<Err = NewsApiError>>(&mut self, name: &str) -> Option<T> {
+        let value = self.string(name)?;
+        value.parse().map_err(|err| self.reject(err)).ok()
+    }
+
+    pub(crate) fn number(&mut self, name: &str) -> Option<u32> {
+        let value = self.string(name)?;
+        match value.parse() {
+            Ok(number) => Some(number),
+            Err(_) => {
+                self.violations
+                    .push(format!("{} `{}` is not a whole number", name, value));
+                None
+            }
+        }
+    }
+
+    pub(crate) fn list(&mut self, name: &str) -> Vec<String> {
+        match self.string(name) {
+            Some(value) => value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub(crate) fn values<T: FromStr<Err = NewsApiError>>(&mut self, name: &str) -> Vec<T> {
+        let mut values = Vec::new();
+        for item in self.list(name) {
+            match item.parse() {
+                Ok(value) => values.push(value),
+                Err(err) => self.reject(err),
+            }
+        }
+        values
+    }
+
+    /// A time in any of the forms the API accepts: RFC 3339, a UTC time
+    /// without offset, or a date.
+    #[cfg(feature = "chrono")]
+    pub(crate) fn timestamp(&mut self, name: &str) -> Option<Timestamp> {
+        let value = self.string(name)?;
+        let timestamp = DateTime::parse_from_rfc3339(&value)
+            .map(|time| time.with_timezone(&Utc))
+            .or_else(|_| {
+                NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S%.f")
+                    .map(|time| Utc.from_utc_datetime(&time))
+            })
+            .or_else(|_| {
+                NaiveDate::parse_from_str(&value, "%Y-%m-%d").map(|date| {
+                    Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).expect("midnight exists"))
+                })
+            });
+        match timestamp {
+            Ok(timestamp) => Some(timestamp),
+            Err(_) => {
+                self.violations
+                    .push(format!("{} `{}` is not a date or time", name, value));
+                None
+            }
+        }
+    }
+
+    /// The time as written, which can only be checked with the `chrono`
+    /// feature.
+    #[cfg(not(feature = "chrono"))]
+    pub(crate) fn timestamp(&mut self, name: &str) -> Option<Timestamp> {
+        self.string(name)
+    }
+
+    /// Reports the parameters nobody asked for, along with any bad values.
+    pub(crate) fn finish(mut self) -> Result<(), NewsApiError> {
+        for (name, _) in self.pairs {
+            self.violations
+                .push(format!("unknown parameter `{}`", name));
+        }
+        self.violations.into_result()
+    }
+
+    fn reject(&mut self, err: NewsApiError) {
+        self.violations.push(err.to_string());
+    }
+}
@@ -0,0 +1,179 @@
+use crate::validate::Violations;
+use crate::NewsApiError;
+use std::fmt;
+use std::ops::Not;
+
+/// The longest `q` the API accepts, in characters.
+pub const MAX_QUERY_LENGTH: usize = 500;
+
+/// A search in NewsAPI's advanced query syntax, for the `q` parameter.
+///
+/// Terms are written bare when that is unambiguous and quoted otherwise:
+/// when they contain whitespace or syntax characters, start with `+` or `-`,
+/// or are one of the operators `AND`, `OR` and `NOT`. Sub-queries are
+/// parenthesised wherever precedence requires it, and `AND` and `OR` groups
+/// nested in another group always are, as the API does not document how the
+/// two bind relative to each other.
+///
+/// The syntax has no escape for a double quote, so terms containing one
+/// cannot be expressed, and neither can empty terms or groups. [`Query::build`]
+/// rejects those rather than send a different search.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Query {
+    Term(String),
+    Phrase(String),
+    /// `+query`: must appear.
+    Must(Box<Query>),
+    /// `-query`: must not appear.
+    MustNot(Box<Query>),
+    Not(Box<Query>),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+}
+
+impl Query {
+    pub fn term(term: &str) -> Query {
+        Query::Term(term.to_string())
+    }
+
+    /// A phrase that must appear exactly, always quoted.
+    pub fn phrase(phrase: &str) -> Query {
+        Query::Phrase(phrase.to_string())
+    }
+
+    pub fn must(query: Query) -> Query {
+        Query::Must(Box::new(query))
+    }
+
+    pub fn must_not(query: Query) -> Query {
+        Query::MustNot(Box::new(query))
+    }
+
+    pub fn and(self, other: Query) -> Query {
+        match self {
+            Query::And(mut queries) => {
+                queries.push(other);
+                Query::And(queries)
+            }
+            query => Query::And(vec![query, other]),
+        }
+    }
+
+    pub fn or(self, other: Query) -> Query {
+        match self {
+            Query::Or(mut queries) => {
+                queries.push(other);
+                Query::Or(queries)
+            }
+            query => Query::Or(vec![query, other]),
+        }
+    }
+
+    /// Renders the query, failing with [`NewsApiError::InvalidRequest`] if it
+    /// cannot be expressed in the syntax or is longer than the API accepts.
+    pub fn build(&self) -> Result<String, NewsApiError> {
+        let query = self.to_string();
+        let mut violations = Violations::default();
+        self.check(&mut violations);
+        violations.check_query(Some(&query));
+        violations.into_result()?;
+        Ok(query)
+    }
+
+    /// Collects the parts of the query that the syntax cannot express.
+    fn check(&self, violations: &mut Violations) {
+        match self {
+            Query::Term(term) | Query::Phrase(term) => {
+                if term.trim().is_empty() {
+                    violations.push("the query contains an empty term".to_string());
+                } else if term.contains('"') {
+                    violations.push(format!(
+                        "term `{}` contains a double quote, which the query syntax cannot express",
+                        term
+                    ));
+                }
+            }
+            Query::Must(query) | Query::MustNot(query) | Query::Not(query) => {
+                query.check(violations)
+            }
+            Query::And(queries) | Query::Or(queries) => {
+                if queries.is_empty() {
+                    violations.push("the query contains an empty AND or OR group".to_string());
+                }
+                for query in queries {
+                    query.check(violations);
+                }
+            }
+        }
+    }
+
+    /// Binding strength, higher binding tighter.
+    fn precedence(&self) -> u8 {
+        match self {
+            Query::Or(_) => 0,
+            Query::And(_) => 1,
+            Query::Must(_) | Query::MustNot(_) | Query::Not(_) => 2,
+            Query::Term(_) | Query::Phrase(_) => 3,
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter<'_>, parenthesise: bool) -> fmt::Result {
+        if parenthesise {
+            write!(f, "({})", self)
+        } else {
+            write!(f, "{}", self)
+        }
+    }
+
+    fn fmt_joined(f: &mut fmt::Formatter<'_>, queries: &[Query], operator: &str) -> fmt::Result {
+        for (i, query) in queries.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", operator)?;
+            }
+            query.fmt_operand(f, matches!(query, Query::And(_) | Query::Or(_)))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Query::Term(term) if is_bare(term) => write!(f, "{}", term),
+            Query::Term(term) | Query::Phrase(term) => write!(f, "\"{}\"", term),
+            Query::Must(query) => {
+                write!(f, "+")?;
+                query.fmt_operand(f, query.precedence() < 3)
+            }
+            Query::MustNot(query) => {
+                write!(f, "-")?;
+                query.fmt_operand(f, query.precedence() < 3)
+            }
+            Query::Not(query) => {
+                write!(f, "NOT ")?;
+                query.fmt_operand(f, query.precedence() < 3)
+            }
+            Query::And(queries) => Query::fmt_joined(f, queries, "AND"),
+            Query::Or(queries) => Query::fmt_joined(f, queries, "OR"),
+        }
+    }
+}
+
+impl Not for Query {
+    type Output = Query;
+
+    /// `NOT query`.
+    fn not(self) -> Query {
+        Query::Not(Box::new(self))
+    }
+}
+
+/// Whether `term` reads as a single term without quotes.
+fn is_bare(term: &str) -> bool {
+    !term.is_empty()
+        && !matches!(term, "AND" | "OR" | "NOT")
+        && !term.starts_with(['+', '-'])
+        && !term
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '"' | '(' | ')'))
+}
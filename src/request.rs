@@ -0,0 +1,496 @@
+use crate::parse::Params;
+use crate::validate::{is_blank, Violations};
+use crate::{
+    AnyResponse, Category, Country, Endpoint, Language, NewsAPIResponse, NewsApiError, Query,
+    SearchIn, SortBy, SourcesResponse, Timestamp,
+};
+#[cfg(feature = "chrono")]
+use chrono::SecondsFormat;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use url::Url;
+
+/// A request for one endpoint of the API, sent with [`crate::NewsAPI::fetch`].
+pub trait Request {
+    /// What the endpoint responds with.
+    type Response: DeserializeOwned;
+
+    fn endpoint(&self) -> Endpoint;
+
+    /// The query parameters of the request, in the order they appear in its URL.
+    fn query_pairs(&self) -> Vec<(&'static str, String)>;
+
+    /// Checks for mistakes the API would reject, so they do not cost quota,
+    /// and reports all of them at once as [`NewsApiError::InvalidRequest`].
+    fn validate(&self) -> Result<(), NewsApiError>;
+}
+
+/// A request whose articles are split into pages, see [`crate::NewsAPI::pages`].
+pub trait PagedRequest: Request<Response = NewsAPIResponse> + Clone {
+    /// The page asked for and the number of articles per page, if set.
+    fn paging(&self) -> (Option<u32>, Option<u32>);
+
+    /// The same request for page `page` of `page_size` articles.
+    fn with_paging(&self, page: u32, page_size: u32) -> Self;
+}
+
+/// Live top headlines, from `/v2/top-headlines`.
+///
+/// The API rejects `sources` combined with `country` or `category`, and
+/// requires at least one of `country`, `category`, `sources` or `q`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TopHeadlinesRequest {
+    country: Option<Country>,
+    category: Option<Category>,
+    sources: Vec<String>,
+    q: Option<String>,
+    page_size: Option<u32>,
+    page: Option<u32>,
+}
+
+impl TopHeadlinesRequest {
+    pub fn new() -> TopHeadlinesRequest {
+        TopHeadlinesRequest::default()
+    }
+
+    /// Parses the query string of a top-headlines URL, with or without its
+    /// leading `?`, reporting unknown parameters and invalid values.
+    pub fn from_query(query: &str) -> Result<TopHeadlinesRequest, NewsApiError> {
+        let mut params = Params::new(query);
+        let request = TopHeadlinesRequest {
+            country: params.value("country"),
+            category: params.value("category"),
+            sources: params.list("sources"),
+            q: params.string("q"),
+            page_size: params.number("pageSize"),
+            page: params.number("page"),
+        };
+        params.finish()?;
+        Ok(request)
+    }
+
+    pub fn country(mut self, country: Country) -> TopHeadlinesRequest {
+        self.country = Some(country);
+        self
+    }
+
+    pub fn category(mut self, category: Category) -> TopHeadlinesRequest {
+        self.category = Some(category);
+        self
+    }
+
+    pub fn sources(mut self, sources: &[&str]) -> TopHeadlinesRequest {
+        self.sources = to_strings(sources);
+        self
+    }
+
+    pub fn query(mut self, query: &str) -> TopHeadlinesRequest {
+        self.q = Some(query.to_string());
+        self
+    }
+
+    /// Sets `q` from a typed [`Query`], failing if [`Query::build`] does.
+    pub fn search(mut self, query: &Query) -> Result<TopHeadlinesRequest, NewsApiError> {
+        self.q = Some(query.build()?);
+        Ok(self)
+    }
+
+    pub fn page_size(mut self, page_size: u32) -> TopHeadlinesRequest {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    pub fn page(mut self, page: u32) -> TopHeadlinesRequest {
+        self.page = Some(page);
+        self
+    }
+}
+
+impl Request for TopHeadlinesRequest {
+    type Response = NewsAPIResponse;
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::TopHeadlines
+    }
+
+    fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+        push(&mut pairs, "country", &self.country);
+        push(&mut pairs, "category", &self.category);
+        push_list(&mut pairs, "sources", &self.sources);
+        push(&mut pairs, "q", &self.q);
+        push(&mut pairs, "pageSize", &self.page_size);
+        push(&mut pairs, "page", &self.page);
+        pairs
+    }
+
+    fn validate(&self) -> Result<(), NewsApiError> {
+        let mut violations = Violations::default();
+        violations.check_query(self.q.as_deref());
+        violations.check_sources(&self.sources);
+        violations.check_paging(self.page, self.page_size);
+        if !self.sources.is_empty() && (self.country.is_some() || self.category.is_some()) {
+            violations.push("sources cannot be mixed with country or category".to_string());
+        }
+        if self.sources.is_empty()
+            && self.country.is_none()
+            && self.category.is_none()
+            && is_blank(self.q.as_deref())
+        {
+            violations.push(
+                "top-headlines needs at least one of country, category, sources or q".to_string(),
+            );
+        }
+        violations.into_result()
+    }
+}
+
+impl PagedRequest for TopHeadlinesRequest {
+    fn paging(&self) -> (Option<u32>, Option<u32>) {
+        (self.page, self.page_size)
+    }
+
+    fn with_paging(&self, page: u32, page_size: u32) -> TopHeadlinesRequest {
+        self.clone().page(page).page_size(page_size)
+    }
+}
+
+/// A search of every article in the archive, from `/v2/everything`.
+///
+/// The API requires at least one of `q`, `sources` or `domains`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EverythingRequest {
+    q: Option<String>,
+    search_in: Vec<SearchIn>,
+    sources: Vec<String>,
+    domains: Vec<String>,
+    exclude_domains: Vec<String>,
+    from: Option<Timestamp>,
+    to: Option<Timestamp>,
+    language: Option<Language>,
+    sort_by: Option<SortBy>,
+    page_size: Option<u32>,
+    page: Option<u32>,
+}
+
+impl EverythingRequest {
+    pub fn new() -> EverythingRequest {
+        EverythingRequest::default()
+    }
+
+    /// Parses the query string of an everything URL, with or without its
+    /// leading `?`, reporting unknown parameters and invalid values.
+    pub fn from_query(query: &str) -> Result<EverythingRequest, NewsApiError> {
+        let mut params = Params::new(query);
+        let request = EverythingRequest {
+            q: params.string("q"),
+            search_in: params.values("searchIn"),
+            sources: params.list("sources"),
+            domains: params.list("domains"),
+            exclude_domains: params.list("excludeDomains"),
+            from: params.timestamp("from"),
+            to: params.timestamp("to"),
+            language: params.value("language"),
+            sort_by: params.value("sortBy"),
+            page_size: params.number("pageSize"),
+            page: params.number("page"),
+        };
+        params.finish()?;
+        Ok(request)
+    }
+
+    pub fn query(mut self, query: &str) -> EverythingRequest {
+        self.q = Some(query.to_string());
+        self
+    }
+
+    /// Sets `q` from a typed [`Query`], failing if [`Query::build`] does.
+    pub fn search(mut self, query: &Query) -> Result<EverythingRequest, NewsApiError> {
+        self.q = Some(query.build()?);
+        Ok(self)
+    }
+
+    pub fn search_in(mut self, search_in: Vec<SearchIn>) -> EverythingRequest {
+        self.search_in = search_in;
+        self
+    }
+
+    pub fn sources(mut self, sources: &[&str]) -> EverythingRequest {
+        self.sources = to_strings(sources);
+        self
+    }
+
+    pub fn domains(mut self, domains: &[&str]) -> EverythingRequest {
+        self.domains = to_strings(domains);
+        self
+    }
+
+    pub fn exclude_domains(mut self, domains: &[&str]) -> EverythingRequest {
+        self.exclude_domains = to_strings(domains);
+        self
+    }
+
+    pub fn from(mut self, from: Timestamp) -> EverythingRequest {
+        self.from = Some(from);
+        self
+    }
+
+    pub fn to(mut self, to: Timestamp) -> EverythingRequest {
+        self.to = Some(to);
+        self
+    }
+
+    pub fn language(mut self, language: Language) -> EverythingRequest {
+        self.language = Some(language);
+        self
+    }
+
+    pub fn sort_by(mut self, sort_by: SortBy) -> EverythingRequest {
+        self.sort_by = Some(sort_by);
+        self
+    }
+
+    pub fn page_size(mut self, page_size: u32) -> EverythingRequest {
+        self.page_size = Some(page_size);
+        self
+    }
+
+    pub fn page(mut self, page: u32) -> EverythingRequest {
+        self.page = Some(page);
+        self
+    }
+}
+
+impl Request for EverythingRequest {
+    type Response = NewsAPIResponse;
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::Everything
+    }
+
+    fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+        push(&mut pairs, "q", &self.q);
+        push_list(&mut pairs, "searchIn", &self.search_in);
+        push_list(&mut pairs, "sources", &self.sources);
+        push_list(&mut pairs, "domains", &self.domains);
+        push_list(&mut pairs, "excludeDomains", &self.exclude_domains);
+        if let Some(from) = &self.from {
+            pairs.push(("from", format_timestamp(from)));
+        }
+        if let Some(to) = &self.to {
+            pairs.push(("to", format_timestamp(to)));
+        }
+        push(&mut pairs, "language", &self.language);
+        push(&mut pairs, "sortBy", &self.sort_by);
+        push(&mut pairs, "pageSize", &self.page_size);
+        push(&mut pairs, "page", &self.page);
+        pairs
+    }
+
+    fn validate(&self) -> Result<(), NewsApiError> {
+        let mut violations = Violations::default();
+        violations.check_query(self.q.as_deref());
+        violations.check_sources(&self.sources);
+        violations.check_paging(self.page, self.page_size);
+        if is_blank(self.q.as_deref()) && self.sources.is_empty() && self.domains.is_empty() {
+            violations.push("everything needs at least one of q, sources or domains".to_string());
+        }
+        violations.check_range(self.from.as_ref(), self.to.as_ref());
+        violations.into_result()
+    }
+}
+
+impl PagedRequest for EverythingRequest {
+    fn paging(&self) -> (Option<u32>, Option<u32>) {
+        (self.page, self.page_size)
+    }
+
+    fn with_paging(&self, page: u32, page_size: u32) -> EverythingRequest {
+        self.clone().page(page).page_size(page_size)
+    }
+}
+
+/// The publishers behind the top headlines, from `/v2/top-headlines/sources`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SourcesRequest {
+    category: Option<Category>,
+    language: Option<Language>,
+    country: Option<Country>,
+}
+
+impl SourcesRequest {
+    pub fn new() -> SourcesRequest {
+        SourcesRequest::default()
+    }
+
+    /// Parses the query string of a sources URL, with or without its leading
+    /// `?`, reporting unknown parameters and invalid values.
+    pub fn from_query(query: &str) -> Result<SourcesRequest, NewsApiError> {
+        let mut params = Params::new(query);
+        let request = SourcesRequest {
+            category: params.value("category"),
+            language: params.value("language"),
+            country: params.value("country"),
+        };
+        params.finish()?;
+        Ok(request)
+    }
+
+    pub fn category(mut self, category: Category) -> SourcesRequest {
+        self.category = Some(category);
+        self
+    }
+
+    pub fn language(mut self, language: Language) -> SourcesRequest {
+        self.language = Some(language);
+        self
+    }
+
+    pub fn country(mut self, country: Country) -> SourcesRequest {
+        self.country = Some(country);
+        self
+    }
+}
+
+impl Request for SourcesRequest {
+    type Response = SourcesResponse;
+
+    fn endpoint(&self) -> Endpoint {
+        Endpoint::Sources
+    }
+
+    fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+        push(&mut pairs, "category", &self.category);
+        push(&mut pairs, "language", &self.language);
+        push(&mut pairs, "country", &self.country);
+        pairs
+    }
+
+    fn validate(&self) -> Result<(), NewsApiError> {
+        Ok(())
+    }
+}
+
+/// A request for any endpoint, as read back from a URL by
+/// [`AnyRequest::from_url`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "endpoint", rename_all = "kebab-case")]
+pub enum AnyRequest {
+    TopHeadlines(TopHeadlinesRequest),
+    Everything(EverythingRequest),
+    Sources(SourcesRequest),
+}
+
+impl AnyRequest {
+    /// Parses a URL such as `https://newsapi.org/v2/everything?q=rust`. The
+    /// endpoint is taken from the end of the path, so URLs of proxies and
+    /// mock servers with a different base URL are understood too.
+    pub fn from_url(url: &str) -> Result<AnyRequest, NewsApiError> {
+        let url = Url::parse(url)?;
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        let query = url.query().unwrap_or("");
+        match segments.as_slice() {
+            [.., "top-headlines", "sources"] => {
+                SourcesRequest::from_query(query).map(Self::Sources)
+            }
+            [.., "top-headlines"] => TopHeadlinesRequest::from_query(query).map(Self::TopHeadlines),
+            [.., "everything"] => EverythingRequest::from_query(query).map(Self::Everything),
+            _ => Err(NewsApiError::InvalidRequest(vec![format!(
+                "`{}` is not a NewsAPI endpoint",
+                url.path()
+            )])),
+        }
+    }
+}
+
+impl Request for AnyRequest {
+    type Response = AnyResponse;
+
+    fn endpoint(&self) -> Endpoint {
+        match self {
+            Self::TopHeadlines(request) => request.endpoint(),
+            Self::Everything(request) => request.endpoint(),
+            Self::Sources(request) => request.endpoint(),
+        }
+    }
+
+    fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::TopHeadlines(request) => request.query_pairs(),
+            Self::Everything(request) => request.query_pairs(),
+            Self::Sources(request) => request.query_pairs(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), NewsApiError> {
+        match self {
+            Self::TopHeadlines(request) => request.validate(),
+            Self::Everything(request) => request.validate(),
+            Self::Sources(request) => request.validate(),
+        }
+    }
+}
+
+impl From<TopHeadlinesRequest> for AnyRequest {
+    fn from(request: TopHeadlinesRequest) -> Self {
+        Self::TopHeadlines(request)
+    }
+}
+
+impl From<EverythingRequest> for AnyRequest {
+    fn from(request: EverythingRequest) -> Self {
+        Self::Everything(request)
+    }
+}
+
+impl From<SourcesRequest> for AnyRequest {
+    fn from(request: SourcesRequest) -> Self {
+        Self::Sources(request)
+    }
+}
+
+fn push<T: fmt::Display>(
+    pairs: &mut Vec<(&'static str, String)>,
+    name: &'static str,
+    value: &Option<T>,
+) {
+    if let Some(value) = value {
+        pairs.push((name, value.to_string()));
+    }
+}
+
+fn push_list<T: fmt::Display>(
+    pairs: &mut Vec<(&'static str, String)>,
+    name: &'static str,
+    values: &[T],
+) {
+    if !values.is_empty() {
+        let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+        pairs.push((name, values.join(",")));
+    }
+}
+
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+/// RFC 3339 in UTC, with as many fractional digits as the timestamp needs.
+#[cfg(feature = "chrono")]
+fn format_timestamp(timestamp: &Timestamp) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Without chrono, timestamps are sent as they were given.
+#[cfg(not(feature = "chrono"))]
+fn format_timestamp(timestamp: &Timestamp) -> String {
+    timestamp.clone()
+}
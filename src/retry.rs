@@ -0,0 +1,83 @@
+use crate::HttpResponse;
+#[cfg(feature = "chrono")]
+use chrono::DateTime;
+#[cfg(feature = "chrono")]
+use std::convert::TryFrom;
+use std::time::Duration;
+#[cfg(feature = "chrono")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How [`crate::NewsAPI`] retries requests that failed for a reason that may
+/// go away on its own, see [`crate::NewsApiError::is_retryable`].
+///
+/// The wait before retry `n` is `initial_backoff * 2^(n - 1)`, capped at
+/// `max_backoff`, with a random jitter taking off up to half of it. A
+/// `Retry-After` header on the failed response replaces the computed wait; if
+/// it asks for longer than `max_backoff`, the error is returned instead.
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Makes at most `max_attempts` attempts per request, counting the first.
+    pub fn new(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> RetryPolicy {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn max_backoff(mut self, max_backoff: Duration) -> RetryPolicy {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// How long to wait after failed attempt number `attempt` (starting at
+    /// 1), or `None` when the request should not be retried.
+    pub(crate) fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        if let Some(retry_after) = retry_after {
+            return Some(retry_after).filter(|delay| *delay <= self.max_backoff);
+        }
+
+        let factor = 2u32.saturating_pow(attempt - 1);
+        let backoff = self
+            .initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+        Some(backoff.mul_f64(1.0 - fastrand::f64() / 2.0))
+    }
+}
+
+/// The wait requested by a `Retry-After` header, given either in seconds or,
+/// with the `chrono` feature, as an HTTP date.
+pub(crate) fn retry_after(response: &HttpResponse) -> Option<Duration> {
+    let value = response.header("Retry-After")?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    until_http_date(value)
+}
+
+#[cfg(feature = "chrono")]
+fn until_http_date(value: &str) -> Option<Duration> {
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let until = UNIX_EPOCH + Duration::from_secs(u64::try_from(date.timestamp()).ok()?);
+    until.duration_since(SystemTime::now()).ok()
+}
+
+#[cfg(not(feature = "chrono"))]
+fn until_http_date(_value: &str) -> Option<Duration> {
+    None
+}
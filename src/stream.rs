@@ -0,0 +1,195 @@
+use crate::pagination::Cursor;
+use crate::{Article, NewsAPI, NewsAPIResponse, NewsApiError, PagedRequest};
+use futures::future::BoxFuture;
+use futures::{ready, FutureExt, Stream};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::vec;
+
+type PageResult = Result<NewsAPIResponse, NewsApiError>;
+
+/// Stream over the pages of a request, created by [`NewsAPI::pages_async`].
+///
+/// Stops under the same conditions as [`crate::Pages`]. With
+/// [`PageStream::prefetch`] enabled the request for the next page is sent as
+/// soon as the current one arrives, and driven while the current page is
+/// being consumed.
+pub struct PageStream<'a, R> {
+    api: &'a NewsAPI,
+    request: R,
+    cursor: Cursor,
+    articles_wanted: Option<usize>,
+    prefetch: bool,
+    in_flight: Option<BoxFuture<'a, PageResult>>,
+    prefetched: Option<PageResult>,
+}
+
+impl<'a, R: PagedRequest> PageStream<'a, R> {
+    pub(crate) fn new(api: &'a NewsAPI, request: R) -> PageStream<'a, R> {
+        PageStream {
+            api,
+            cursor: Cursor::new(&request),
+            request,
+            articles_wanted: None,
+            prefetch: false,
+            in_flight: None,
+            prefetched: None,
+        }
+    }
+
+    /// Stops after `max_pages` requests, whatever `totalResults` says.
+    pub fn max_pages(mut self, max_pages: u32) -> PageStream<'a, R> {
+        self.cursor.max_pages(max_pages);
+        self
+    }
+
+    /// Requests the next page while the current one is being consumed.
+    pub fn prefetch(mut self, prefetch: bool) -> PageStream<'a, R> {
+        self.prefetch = prefetch;
+        self
+    }
+
+    /// Stops requesting pages once `articles` articles have arrived, as the
+    /// consumer will not look at any more.
+    pub(crate) fn articles_wanted(mut self, articles: usize) -> PageStream<'a, R> {
+        self.articles_wanted = Some(articles);
+        self
+    }
+
+    fn can_request(&self) -> bool {
+        self.cursor.can_request() && self.articles_wanted != Some(0)
+    }
+
+    fn request_next(&mut self) {
+        let api = self.api;
+        let request = self.cursor.next_request(&self.request);
+        let endpoint = request.endpoint();
+        let url = api.prepare_url(&request);
+        self.in_flight = Some(async move { api.get_async(endpoint, &url?).await }.boxed());
+    }
+
+    /// Drives an outstanding prefetch, keeping its result once it completes.
+    pub(crate) fn poll_prefetch(&mut self, cx: &mut Context<'_>) {
+        if let Some(request) = self.in_flight.as_mut() {
+            if let Poll::Ready(result) = request.as_mut().poll(cx) {
+                self.in_flight = None;
+                self.prefetched = Some(result);
+            }
+        }
+    }
+
+    fn complete(&mut self, result: PageResult, cx: &mut Context<'_>) -> Option<PageResult> {
+        match result {
+            Ok(response) => {
+                self.cursor.complete(&response);
+                if let Some(wanted) = self.articles_wanted {
+                    self.articles_wanted = Some(wanted.saturating_sub(response.articles().len()));
+                }
+                if self.prefetch && self.can_request() {
+                    self.request_next();
+                    self.poll_prefetch(cx);
+                }
+                Some(Ok(response))
+            }
+            Err(NewsApiError::MaximumResultsReached(_)) => {
+                self.cursor.stop();
+                None
+            }
+            Err(err) => {
+                self.cursor.stop();
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<'a, R: PagedRequest + Unpin> Stream for PageStream<'a, R> {
+    type Item = PageResult;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Some(result) = this.prefetched.take() {
+            return Poll::Ready(this.complete(result, cx));
+        }
+
+        if this.in_flight.is_none() {
+            if !this.can_request() {
+                return Poll::Ready(None);
+            }
+            this.request_next();
+        }
+
+        let request = this.in_flight.as_mut().expect("a request is in flight");
+        let result = ready!(request.as_mut().poll(cx));
+        this.in_flight = None;
+        Poll::Ready(this.complete(result, cx))
+    }
+}
+
+/// Stream over the articles of every page of a request, created by
+/// [`NewsAPI::articles_async`].
+pub struct ArticleStream<'a, R> {
+    pages: PageStream<'a, R>,
+    current: vec::IntoIter<Article>,
+    max_articles: Option<usize>,
+    yielded: usize,
+}
+
+impl<'a, R: PagedRequest> ArticleStream<'a, R> {
+    pub(crate) fn new(pages: PageStream<'a, R>) -> ArticleStream<'a, R> {
+        ArticleStream {
+            pages,
+            current: Vec::new().into_iter(),
+            max_articles: None,
+            yielded: 0,
+        }
+    }
+
+    /// Stops after `max_pages` requests.
+    pub fn max_pages(mut self, max_pages: u32) -> ArticleStream<'a, R> {
+        self.pages = self.pages.max_pages(max_pages);
+        self
+    }
+
+    /// Stops after yielding `max_articles` articles, without requesting
+    /// pages that are not needed to reach it.
+    pub fn max_articles(mut self, max_articles: usize) -> ArticleStream<'a, R> {
+        self.pages = self.pages.articles_wanted(max_articles);
+        self.max_articles = Some(max_articles);
+        self
+    }
+
+    /// Requests the next page while the articles of the current one are
+    /// being consumed.
+    pub fn prefetch(mut self, prefetch: bool) -> ArticleStream<'a, R> {
+        self.pages = self.pages.prefetch(prefetch);
+        self
+    }
+}
+
+impl<'a, R: PagedRequest + Unpin> Stream for ArticleStream<'a, R> {
+    type Item = Result<Article, NewsApiError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.max_articles == Some(this.yielded) {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            if let Some(article) = this.current.next() {
+                this.pages.poll_prefetch(cx);
+                this.yielded += 1;
+                return Poll::Ready(Some(Ok(article)));
+            }
+
+            match ready!(Pin::new(&mut this.pages).poll_next(cx)) {
+                Some(Ok(response)) => this.current = response.into_articles().into_iter(),
+                Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
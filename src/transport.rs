@@ -0,0 +1,293 @@
+use crate::NewsApiError;
+#[cfg(feature = "async")]
+use futures::future::{BoxFuture, FutureExt};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+/// A prepared `GET` request to the API. Its `Debug` output masks the API
+/// key, wherever it is.
+#[derive(Clone)]
+pub struct HttpRequest {
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    pub fn new(url: &str) -> HttpRequest {
+        HttpRequest {
+            url: url.to_string(),
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> HttpRequest {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+}
+
+impl fmt::Debug for HttpRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let headers: Vec<(&str, &str)> = self
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                if name.eq_ignore_ascii_case("X-Api-Key")
+                    || name.eq_ignore_ascii_case("Authorization")
+                {
+                    (name.as_str(), "***")
+                } else {
+                    (name.as_str(), value.as_str())
+                }
+            })
+            .collect();
+        f.debug_struct("HttpRequest")
+            .field("url", &rewrite_api_key(&self.url, Some("***")))
+            .field("headers", &headers)
+            .finish()
+    }
+}
+
+/// The raw response to an [`HttpRequest`], whatever its status.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HttpResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, body: &str) -> HttpResponse {
+        HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.to_string(),
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> HttpResponse {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn status(&self) -> u16 {
+        self.status
+    }
+
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+
+    /// The value of the first header called `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Sends blocking requests on behalf of [`crate::NewsAPI`].
+///
+/// Non-2xx statuses are responses, not errors: only failures to get a
+/// response at all should be returned as `Err`.
+pub trait Transport: Send + Sync {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, NewsApiError>;
+}
+
+/// Sends async requests on behalf of [`crate::NewsAPI`], with the same
+/// contract as [`Transport`].
+#[cfg(feature = "async")]
+pub trait AsyncTransport: Send + Sync {
+    fn send<'a>(
+        &'a self,
+        request: &'a HttpRequest,
+    ) -> BoxFuture<'a, Result<HttpResponse, NewsApiError>>;
+}
+
+impl Transport for ureq::Agent {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, NewsApiError> {
+        let req = request
+            .headers()
+            .iter()
+            .fold(self.get(request.url()), |req, (name, value)| {
+                req.set(name, value)
+            });
+        let response = match req.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(err) => return Err(err.into()),
+        };
+        let headers = response
+            .headers_names()
+            .into_iter()
+            .filter_map(|name| {
+                let value = response.header(&name)?.to_string();
+                Some((name, value))
+            })
+            .collect();
+        Ok(HttpResponse {
+            status: response.status(),
+            headers,
+            body: response.into_string()?,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for reqwest::Client {
+    fn send<'a>(
+        &'a self,
+        request: &'a HttpRequest,
+    ) -> BoxFuture<'a, Result<HttpResponse, NewsApiError>> {
+        async move {
+            let req = request
+                .headers()
+                .iter()
+                .fold(self.get(request.url()), |req, (name, value)| {
+                    req.header(name.as_str(), value.as_str())
+                });
+            let response = req.send().await?;
+            let status = response.status().as_u16();
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect();
+            Ok(HttpResponse {
+                status,
+                headers,
+                body: response.text().await?,
+            })
+        }
+        .boxed()
+    }
+}
+
+/// Serves canned responses from memory, so code built on [`crate::NewsAPI`]
+/// can be tested without a network.
+///
+/// A route matches a request when the paths are equal and every query pair
+/// of the route is present in the request, so `/v2/everything?page=2` only
+/// answers for the second page. Routes are tried in the order they were
+/// added. Requests that match no route get a 404. Clones share their routes
+/// and the log of received requests.
+#[derive(Clone, Default)]
+pub struct MemoryTransport {
+    inner: Arc<Mutex<MemoryState>>,
+}
+
+#[derive(Default)]
+struct MemoryState {
+    routes: Vec<(Url, HttpResponse)>,
+    requests: Vec<HttpRequest>,
+}
+
+impl MemoryTransport {
+    pub fn new() -> MemoryTransport {
+        MemoryTransport::default()
+    }
+
+    /// Answers requests matching `route` with `status` and the JSON `body`.
+    pub fn route(&self, route: &str, status: u16, body: &str) -> &MemoryTransport {
+        self.route_response(route, HttpResponse::new(status, body))
+    }
+
+    /// Answers requests matching `route` with `response`.
+    pub fn route_response(&self, route: &str, response: HttpResponse) -> &MemoryTransport {
+        let route = Url::parse("memory://transport")
+            .and_then(|base| base.join(route))
+            .expect("route must be a valid URL path");
+        self.inner.lock().unwrap().routes.push((route, response));
+        self
+    }
+
+    /// Every request received so far, oldest first.
+    pub fn requests(&self) -> Vec<HttpRequest> {
+        self.inner.lock().unwrap().requests.clone()
+    }
+
+    fn respond(&self, request: &HttpRequest) -> Result<HttpResponse, NewsApiError> {
+        let url = Url::parse(request.url())?;
+        let mut state = self.inner.lock().unwrap();
+        state.requests.push(request.clone());
+        let response = state
+            .routes
+            .iter()
+            .find(|(route, _)| {
+                route.path() == url.path()
+                    && route
+                        .query_pairs()
+                        .all(|pair| url.query_pairs().any(|other| other == pair))
+            })
+            .map(|(_, response)| response.clone())
+            .unwrap_or_else(|| {
+                HttpResponse::new(404, &format!("no canned response for {}", url.path()))
+            });
+        Ok(response)
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn send(&self, request: &HttpRequest) -> Result<HttpResponse, NewsApiError> {
+        self.respond(request)
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for MemoryTransport {
+    fn send<'a>(
+        &'a self,
+        request: &'a HttpRequest,
+    ) -> BoxFuture<'a, Result<HttpResponse, NewsApiError>> {
+        futures::future::ready(self.respond(request)).boxed()
+    }
+}
+
+/// `url` without its `apiKey` query parameter, for use wherever a URL is
+/// kept around.
+pub(crate) fn without_api_key(url: &str) -> String {
+    rewrite_api_key(url, None)
+}
+
+/// `url` with the value of its `apiKey` query parameter replaced, or the
+/// parameter removed if there is no `replacement`.
+fn rewrite_api_key(url: &str, replacement: Option<&str>) -> String {
+    let mut url = match Url::parse(url) {
+        Ok(url) => url,
+        Err(_) => return url.to_string(),
+    };
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter_map(|(name, value)| match (name == "apiKey", replacement) {
+            (false, _) => Some((name.into_owned(), value.into_owned())),
+            (true, Some(replacement)) => Some((name.into_owned(), replacement.to_string())),
+            (true, None) => None,
+        })
+        .collect();
+    url.set_query(None);
+    if !pairs.is_empty() {
+        url.query_pairs_mut().extend_pairs(pairs);
+    }
+    url.to_string()
+}
@@ -0,0 +1,80 @@
+use crate::pagination::MAX_PAGE_SIZE;
+use crate::query::MAX_QUERY_LENGTH;
+use crate::{NewsApiError, Timestamp};
+
+/// The most sources a single request may name.
+pub const MAX_SOURCES: usize = 20;
+
+/// Whether `q` is missing or only whitespace, which searches for nothing.
+pub(crate) fn is_blank(q: Option<&str>) -> bool {
+    q.map_or(true, |q| q.trim().is_empty())
+}
+
+/// The reasons the API would reject a request, collected so they can be
+/// reported all at once.
+#[derive(Default)]
+pub(crate) struct Violations(Vec<String>);
+
+impl Violations {
+    pub(crate) fn push(&mut self, violation: String) {
+        self.0.push(violation);
+    }
+
+    pub(crate) fn check_query(&mut self, q: Option<&str>) {
+        if let Some(q) = q {
+            let length = q.chars().count();
+            if length > MAX_QUERY_LENGTH {
+                self.push(format!(
+                    "q is {} characters long, the maximum is {}",
+                    length, MAX_QUERY_LENGTH
+                ));
+            }
+        }
+    }
+
+    pub(crate) fn check_sources(&mut self, sources: &[String]) {
+        if sources.len() > MAX_SOURCES {
+            self.push(format!(
+                "{} sources given, the maximum is {}",
+                sources.len(),
+                MAX_SOURCES
+            ));
+        }
+    }
+
+    pub(crate) fn check_paging(&mut self, page: Option<u32>, page_size: Option<u32>) {
+        if let Some(page_size) = page_size {
+            if page_size == 0 || page_size > MAX_PAGE_SIZE {
+                self.push(format!(
+                    "pageSize is {}, it must be between 1 and {}",
+                    page_size, MAX_PAGE_SIZE
+                ));
+            }
+        }
+        if page == Some(0) {
+            self.push("page is 0, pages start at 1".to_string());
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    pub(crate) fn check_range(&mut self, from: Option<&Timestamp>, to: Option<&Timestamp>) {
+        if let (Some(from), Some(to)) = (from, to) {
+            if from > to {
+                self.push(format!("from ({}) is after to ({})", from, to));
+            }
+        }
+    }
+
+    /// Timestamps are only known to be comparable once parsed, which needs
+    /// the `chrono` feature.
+    #[cfg(not(feature = "chrono"))]
+    pub(crate) fn check_range(&mut self, _from: Option<&Timestamp>, _to: Option<&Timestamp>) {}
+
+    pub(crate) fn into_result(self) -> Result<(), NewsApiError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(NewsApiError::InvalidRequest(self.0))
+        }
+    }
+}
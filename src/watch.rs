@@ -0,0 +1,174 @@
+use crate::{file_error, Article, FileKind, NewsAPI, NewsAPIResponse, NewsApiError, Request};
+#[cfg(feature = "async")]
+use futures::stream::{self, BoxStream, StreamExt};
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Re-runs a request every `interval` and yields only articles it
+/// has not yielded before, oldest first. Created by [`NewsAPI::watch`].
+///
+/// Articles are told apart by their canonical URL: without fragment or
+/// `utm_*` tracking parameters. With [`Watch::state_file`] the canonical URLs
+/// already seen are kept on disk, so a restarted watcher does not announce
+/// them again. At most [`Watch::max_seen`] URLs are remembered, the oldest
+/// being forgotten first.
+///
+/// Failed polls are yielded as errors and polling carries on at the next
+/// interval, so a spent [`crate::DailyBudget`] pauses the watcher rather
+/// than ending it.
+pub struct Watch<'a, R> {
+    api: &'a NewsAPI,
+    request: R,
+    interval: Duration,
+    state_file: Option<PathBuf>,
+    max_seen: usize,
+    seen: HashSet<String>,
+    seen_order: VecDeque<String>,
+    pending: VecDeque<Article>,
+    next_poll: Option<Instant>,
+}
+
+impl<'a, R: Request<Response = NewsAPIResponse>> Watch<'a, R> {
+    pub(crate) fn new(api: &'a NewsAPI, request: R, interval: Duration) -> Watch<'a, R> {
+        Watch {
+            api,
+            request,
+            interval,
+            state_file: None,
+            max_seen: 10_000,
+            seen: HashSet::new(),
+            seen_order: VecDeque::new(),
+            pending: VecDeque::new(),
+            next_poll: None,
+        }
+    }
+
+    /// Loads the articles already seen from `path`, if it exists, and keeps
+    /// it up to date after every poll.
+    pub fn state_file<P: Into<PathBuf>>(mut self, path: P) -> Result<Watch<'a, R>, NewsApiError> {
+        let path = path.into();
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                let seen: Vec<String> = serde_json::from_str(&contents)
+                    .map_err(|err| file_error(FileKind::WatchState, &path, err))?;
+                for url in seen {
+                    self.remember(url);
+                }
+            }
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => return Err(file_error(FileKind::WatchState, &path, err)),
+        }
+        self.state_file = Some(path);
+        Ok(self)
+    }
+
+    pub fn max_seen(mut self, max_seen: usize) -> Watch<'a, R> {
+        self.max_seen = max_seen;
+        self
+    }
+
+    /// Streams new articles instead of blocking on them.
+    #[cfg(feature = "async")]
+    pub fn into_stream(self) -> BoxStream<'a, Result<Article, NewsApiError>>
+    where
+        R: Send + Sync + 'a,
+    {
+        stream::unfold(self, |mut watch| async move {
+            loop {
+                if let Some(article) = watch.pending.pop_front() {
+                    return Some((Ok(article), watch));
+                }
+                if let Some(delay) = watch.until_next_poll() {
+                    futures_timer::Delay::new(delay).await;
+                }
+                watch.next_poll = Some(Instant::now() + watch.interval);
+                let response = watch.api.fetch_async(&watch.request).await;
+                if let Err(err) = watch.receive(response.map(|response| response.into_articles())) {
+                    return Some((Err(err), watch));
+                }
+            }
+        })
+        .boxed()
+    }
+
+    fn until_next_poll(&self) -> Option<Duration> {
+        let next_poll = self.next_poll?;
+        next_poll.checked_duration_since(Instant::now())
+    }
+
+    /// Queues the unseen articles of a poll and saves the seen set.
+    fn receive(
+        &mut self,
+        articles: Result<Vec<Article>, NewsApiError>,
+    ) -> Result<(), NewsApiError> {
+        let mut articles = articles?;
+        articles.sort_by(|a, b| a.published_at().cmp(b.published_at()));
+        for article in articles {
+            let url = canonical_url(article.url());
+            if !self.seen.contains(&url) {
+                self.remember(url);
+                self.pending.push_back(article);
+            }
+        }
+        self.save()
+    }
+
+    fn remember(&mut self, url: String) {
+        if self.seen.insert(url.clone()) {
+            self.seen_order.push_back(url);
+        }
+        while self.seen_order.len() > self.max_seen {
+            if let Some(oldest) = self.seen_order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+    }
+
+    fn save(&self) -> Result<(), NewsApiError> {
+        if let Some(path) = &self.state_file {
+            fs::write(path, serde_json::to_string(&self.seen_order)?)
+                .map_err(|err| file_error(FileKind::WatchState, path, err))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, R: Request<Response = NewsAPIResponse>> Iterator for Watch<'a, R> {
+    type Item = Result<Article, NewsApiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(article) = self.pending.pop_front() {
+                return Some(Ok(article));
+            }
+            if let Some(delay) = self.until_next_poll() {
+                std::thread::sleep(delay);
+            }
+            self.next_poll = Some(Instant::now() + self.interval);
+            let response = self.api.fetch(&self.request);
+            if let Err(err) = self.receive(response.map(|response| response.into_articles())) {
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+/// `url` without fragment or `utm_*` tracking parameters.
+fn canonical_url(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| !name.starts_with("utm_"))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    url.set_query(None);
+    if !pairs.is_empty() {
+        url.query_pairs_mut().extend_pairs(pairs);
+    }
+    url.to_string()
+}
@@ -0,0 +1,93 @@
+use newsapi::{Article, Timestamp};
+use serde_json::json;
+
+fn article(fields: serde_json::Value) -> Article {
+    let mut article = json!({
+        "source": {"id": "bbc-news", "name": "BBC News"},
+        "title": "Title",
+        "author": "Author",
+        "description": "Description",
+        "url": "https://example.com/article",
+        "urlToImage": "https://example.com/image.jpg",
+        "publishedAt": "2022-01-01T12:00:00Z",
+        "content": "Content [+100 chars]",
+    });
+    for (name, value) in fields.as_object().unwrap() {
+        article[name] = value.clone();
+    }
+    serde_json::from_value(article).unwrap()
+}
+
+#[test]
+fn every_field_is_read() {
+    let article = article(json!({}));
+    assert_eq!(article.source().id(), Some("bbc-news"));
+    assert_eq!(article.source().name(), "BBC News");
+    assert_eq!(article.title(), "Title");
+    assert_eq!(article.author(), Some("Author"));
+    assert_eq!(article.description(), Some("Description"));
+    assert_eq!(article.url().as_str(), "https://example.com/article");
+    assert_eq!(
+        article.url_to_image().map(|url| url.as_str()),
+        Some("https://example.com/image.jpg")
+    );
+    assert_eq!(
+        article.published_at(),
+        &"2022-01-01T12:00:00Z".parse::<Timestamp>().unwrap()
+    );
+    assert_eq!(article.content(), Some("Content [+100 chars]"));
+}
+
+#[test]
+fn missing_or_malformed_image_links_are_dropped() {
+    for url_to_image in [
+        json!(null),
+        json!(""),
+        json!("not a url"),
+        json!("//cdn/x.jpg"),
+    ] {
+        let article = article(json!({ "urlToImage": url_to_image }));
+        assert_eq!(article.url_to_image(), None, "{}", url_to_image);
+    }
+
+    let mut fields = serde_json::to_value(article(json!({}))).unwrap();
+    fields.as_object_mut().unwrap().remove("urlToImage");
+    let article: Article = serde_json::from_value(fields).unwrap();
+    assert_eq!(article.url_to_image(), None);
+}
+
+#[test]
+fn optional_text_may_be_null() {
+    let article = article(json!({"author": null, "description": null, "content": null}));
+    assert_eq!(article.author(), None);
+    assert_eq!(article.description(), None);
+    assert_eq!(article.content(), None);
+}
+
+#[test]
+fn a_malformed_article_link_fails_the_article() {
+    let mut fields = serde_json::to_value(article(json!({}))).unwrap();
+    fields["url"] = json!("not a url");
+    assert!(serde_json::from_value::<Article>(fields).is_err());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn publication_times_with_an_offset_are_read_as_utc() {
+    let article = article(json!({"publishedAt": "2022-01-01T14:30:00+02:00"}));
+    assert_eq!(
+        article.published_at(),
+        &"2022-01-01T12:30:00Z".parse::<Timestamp>().unwrap()
+    );
+    assert_eq!(
+        serde_json::to_value(&article).unwrap()["publishedAt"],
+        "2022-01-01T12:30:00Z"
+    );
+}
+
+#[cfg(not(feature = "chrono"))]
+#[test]
+fn publication_times_are_kept_as_sent() {
+    let article = article(json!({"publishedAt": "2022-01-01T14:30:00+02:00"}));
+    assert_eq!(article.published_at(), "2022-01-01T14:30:00+02:00");
+}
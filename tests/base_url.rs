@@ -0,0 +1,65 @@
+mod common;
+
+use common::{request, EMPTY};
+use newsapi::{MemoryTransport, NewsAPI, SourcesRequest};
+
+#[test]
+fn endpoints_are_joined_onto_the_base_url_path() {
+    for base_url in [
+        "http://127.0.0.1:8080/newsapi/v2/",
+        "http://127.0.0.1:8080/newsapi/v2",
+    ] {
+        let mut api = NewsAPI::new("key");
+        api.base_url(base_url);
+        assert_eq!(
+            api.prepare_url(&request()).unwrap(),
+            "http://127.0.0.1:8080/newsapi/v2/everything?q=rust"
+        );
+        assert_eq!(
+            api.prepare_url(&SourcesRequest::new()).unwrap(),
+            "http://127.0.0.1:8080/newsapi/v2/top-headlines/sources"
+        );
+    }
+}
+
+#[test]
+fn the_default_base_url_is_newsapi() {
+    assert_eq!(
+        NewsAPI::new("key").prepare_url(&request()).unwrap(),
+        "https://newsapi.org/v2/everything?q=rust"
+    );
+}
+
+#[test]
+fn a_base_url_without_a_path_gets_the_endpoint_only() {
+    let mut api = NewsAPI::new("key");
+    api.base_url("http://localhost:3000");
+    assert_eq!(
+        api.prepare_url(&request()).unwrap(),
+        "http://localhost:3000/everything?q=rust"
+    );
+}
+
+#[test]
+fn requests_go_to_the_base_url() {
+    let transport = MemoryTransport::new();
+    transport.route("/proxy/v2/everything", 200, EMPTY);
+    let mut api = NewsAPI::new("key");
+    api.base_url("https://proxy.example/proxy/v2")
+        .transport(transport.clone());
+
+    api.fetch(&request()).unwrap();
+    assert_eq!(
+        transport.requests()[0].url(),
+        "https://proxy.example/proxy/v2/everything?q=rust"
+    );
+}
+
+#[test]
+fn base_urls_that_cannot_hold_a_path_are_rejected() {
+    let mut api = NewsAPI::new("key");
+    api.base_url("mailto:news@example.com");
+    assert!(api.prepare_url(&request()).is_err());
+    api.base_url("not a url");
+    assert!(api.prepare_url(&request()).is_err());
+}
@@ -0,0 +1,168 @@
+mod common;
+
+use common::{api, everything, request, temp_path, EMPTY};
+use newsapi::{
+    Cache, CacheEntry, CacheStore, Endpoint, KeyPlacement, MemoryCache, NewsApiError,
+    SourcesRequest,
+};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const ONE: &str = r#"{"status":"ok","totalResults":1,"articles":[]}"#;
+
+#[test]
+fn fresh_entries_are_served_without_a_request() {
+    let transport = everything(200, EMPTY);
+    let mut api = api(&transport);
+    api.cache(Cache::memory(10));
+
+    api.fetch(&request()).unwrap();
+    api.fetch(&request()).unwrap();
+    assert_eq!(transport.requests().len(), 1);
+
+    api.fetch(&request().page(2)).unwrap();
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn expired_entries_are_fetched_again() {
+    let transport = everything(200, EMPTY);
+    let mut api = api(&transport);
+    api.cache(Cache::memory(10).ttl(Duration::from_secs(0)));
+
+    api.fetch(&request()).unwrap();
+    api.fetch(&request()).unwrap();
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn endpoints_can_have_their_own_ttl() {
+    let transport = everything(200, EMPTY);
+    transport.route(
+        "/v2/top-headlines/sources",
+        200,
+        r#"{"status":"ok","sources":[]}"#,
+    );
+    let mut api = api(&transport);
+    api.cache(
+        Cache::memory(10)
+            .ttl(Duration::from_secs(0))
+            .endpoint_ttl(Endpoint::Sources, Duration::from_secs(3600)),
+    );
+
+    for _ in 0..2 {
+        api.fetch(&request()).unwrap();
+        api.fetch(&SourcesRequest::new()).unwrap();
+    }
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[test]
+fn failures_are_not_cached() {
+    let mut api = api(&everything(503, "Service Unavailable"));
+    api.cache(Cache::memory(10));
+    api.fetch(&request()).unwrap_err();
+
+    let working = everything(200, ONE);
+    api.transport(working.clone());
+    assert_eq!(api.fetch(&request()).unwrap().total_results(), 1);
+    assert_eq!(working.requests().len(), 1);
+}
+
+#[test]
+fn stale_entries_are_served_on_error_only_when_allowed() {
+    for serve_stale in [true, false] {
+        let mut api = api(&everything(200, ONE));
+        api.cache(
+            Cache::memory(10)
+                .ttl(Duration::from_secs(0))
+                .serve_stale_on_error(serve_stale),
+        );
+        api.fetch(&request()).unwrap();
+
+        let failing = everything(503, "Service Unavailable");
+        api.transport(failing.clone());
+        let result = api.fetch(&request());
+        assert_eq!(failing.requests().len(), 1);
+        if serve_stale {
+            assert_eq!(result.unwrap().total_results(), 1);
+        } else {
+            assert!(matches!(
+                result,
+                Err(NewsApiError::HttpStatus { status: 503, .. })
+            ));
+        }
+    }
+}
+
+#[test]
+fn memory_caches_evict_the_least_recently_used_entry() {
+    let transport = everything(200, EMPTY);
+    let mut api = api(&transport);
+    api.cache(Cache::memory(2));
+
+    api.fetch(&request().page(1)).unwrap();
+    api.fetch(&request().page(2)).unwrap();
+    api.fetch(&request().page(1)).unwrap();
+    api.fetch(&request().page(3)).unwrap();
+    assert_eq!(transport.requests().len(), 3);
+
+    api.fetch(&request().page(1)).unwrap();
+    assert_eq!(transport.requests().len(), 3);
+    api.fetch(&request().page(2)).unwrap();
+    assert_eq!(transport.requests().len(), 4);
+}
+
+#[test]
+fn disk_caches_outlive_the_client() {
+    let dir = temp_path("cache");
+    let transport = everything(200, ONE);
+
+    let mut api = api(&transport);
+    api.cache(Cache::disk(&dir));
+    api.fetch(&request()).unwrap();
+
+    let mut api = common::api(&transport);
+    api.cache(Cache::disk(&dir));
+    assert_eq!(api.fetch(&request()).unwrap().total_results(), 1);
+    assert_eq!(transport.requests().len(), 1);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// A store that remembers the keys it was asked for.
+struct KeyLog {
+    cache: MemoryCache,
+    keys: Arc<Mutex<Vec<String>>>,
+}
+
+impl CacheStore for KeyLog {
+    fn get(&self, key: &str) -> Result<Option<CacheEntry>, NewsApiError> {
+        self.keys.lock().unwrap().push(key.to_string());
+        self.cache.get(key)
+    }
+
+    fn put(&self, key: &str, entry: CacheEntry) -> Result<(), NewsApiError> {
+        self.keys.lock().unwrap().push(key.to_string());
+        self.cache.put(key, entry)
+    }
+}
+
+#[test]
+fn cache_keys_are_the_canonical_url_without_the_key() {
+    let keys = Arc::new(Mutex::new(Vec::new()));
+    let transport = everything(200, EMPTY);
+    let mut api = api(&transport);
+    api.key_placement(KeyPlacement::QueryParameter)
+        .cache(Cache::new(KeyLog {
+            cache: MemoryCache::new(10),
+            keys: keys.clone(),
+        }));
+
+    api.fetch(&request()).unwrap();
+    assert!(transport.requests()[0].url().contains("apiKey=key"));
+    let keys = keys.lock().unwrap();
+    assert!(!keys.is_empty());
+    for key in keys.iter() {
+        assert_eq!(key, "https://newsapi.org/v2/everything?q=rust");
+    }
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{page, request, temp_path, EMPTY};
+use newsapi::{Cassette, FileKind, KeyPlacement, MemoryTransport, NewsAPI, NewsApiError};
+use std::fs;
+
+fn recording() -> MemoryTransport {
+    let transport = MemoryTransport::new();
+    transport
+        .route("/v2/everything?page=2", 200, &page(3, 3, 1))
+        .route("/v2/everything", 200, EMPTY);
+    transport
+}
+
+#[test]
+fn recorded_interactions_replay_without_the_network() {
+    let path = temp_path("cassette-replay.json");
+    let transport = recording();
+
+    let mut api = NewsAPI::new("secret-key");
+    api.key_placement(KeyPlacement::QueryParameter)
+        .transport(Cassette::record(&path, transport.clone()));
+    api.fetch(&request()).unwrap();
+    api.fetch(&request().page(2)).unwrap();
+    assert_eq!(transport.requests().len(), 2);
+
+    let contents = fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains("secret-key"), "{}", contents);
+
+    let mut api = NewsAPI::new("other-key");
+    api.transport(Cassette::replay(&path).unwrap());
+    assert_eq!(api.fetch(&request()).unwrap().total_results(), 0);
+    let second = api.fetch(&request().page(2)).unwrap();
+    assert_eq!(second.articles()[0].title(), "Article 3");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn interactions_for_one_url_replay_in_order_then_repeat_the_last() {
+    let path = temp_path("cassette-order.json");
+    let mut api = NewsAPI::new("key");
+    api.transport(Cassette::record(&path, recording()));
+    api.fetch(&request()).unwrap();
+
+    let mut interactions: Vec<serde_json::Value> =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let mut later = interactions[0].clone();
+    later["response"]["body"] = page(1, 1, 1).into();
+    interactions.push(later);
+    fs::write(&path, serde_json::to_string(&interactions).unwrap()).unwrap();
+
+    api.transport(Cassette::replay(&path).unwrap());
+    let totals: Vec<u32> = (0..3)
+        .map(|_| api.fetch(&request()).unwrap().total_results())
+        .collect();
+    assert_eq!(totals, vec![0, 1, 1]);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn unrecorded_requests_are_a_cassette_miss() {
+    let path = temp_path("cassette-miss.json");
+    let mut api = NewsAPI::new("key");
+    api.transport(Cassette::record(&path, recording()));
+    api.fetch(&request()).unwrap();
+
+    api.transport(Cassette::replay(&path).unwrap());
+    match api.fetch(&request().page(5)).unwrap_err() {
+        NewsApiError::CassetteMiss(url) => {
+            assert_eq!(url, "https://newsapi.org/v2/everything?q=rust&page=5")
+        }
+        err => panic!("expected CassetteMiss, got {:?}", err),
+    }
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn missing_cassettes_are_reported() {
+    let path = temp_path("cassette-missing.json");
+    match Cassette::replay(&path) {
+        Err(NewsApiError::FileError {
+            kind: FileKind::Cassette,
+            ..
+        }) => {}
+        Err(err) => panic!("expected a cassette FileError, got {:?}", err),
+        Ok(_) => panic!("expected a cassette FileError"),
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn cassettes_only_record_their_own_kind_of_request() {
+    use futures::executor::block_on;
+
+    let path = temp_path("cassette-mode.json");
+    let mut api = NewsAPI::new("key");
+    api.async_transport(Cassette::record(&path, recording()));
+    match block_on(api.fetch_async(&request())).unwrap_err() {
+        NewsApiError::CassetteModeMismatch { recorded, sent } => {
+            assert_eq!((recorded, sent), ("blocking", "async"))
+        }
+        err => panic!("expected CassetteModeMismatch, got {:?}", err),
+    }
+
+    api.transport(Cassette::record_async(&path, recording()));
+    assert!(matches!(
+        api.fetch(&request()),
+        Err(NewsApiError::CassetteModeMismatch {
+            recorded: "async",
+            sent: "blocking"
+        })
+    ));
+    assert!(!path.exists());
+}
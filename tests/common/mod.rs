@@ -0,0 +1,95 @@
+//! Fixtures shared by the integration tests. Not every test binary uses all
+//! of them.
+#![allow(dead_code)]
+
+use newsapi::{EverythingRequest, MemoryTransport, NewsAPI};
+use serde_json::json;
+use std::path::PathBuf;
+
+/// A successful everything response without articles.
+pub const EMPTY: &str = r#"{"status":"ok","totalResults":0,"articles":[]}"#;
+
+/// A search for `rust`, valid for the everything endpoint.
+pub fn request() -> EverythingRequest {
+    EverythingRequest::new().query("rust")
+}
+
+/// A client sending every request, blocking or async, to `transport`.
+pub fn api(transport: &MemoryTransport) -> NewsAPI {
+    let mut api = NewsAPI::new("key");
+    api.transport(transport.clone());
+    #[cfg(feature = "async")]
+    api.async_transport(transport.clone());
+    api
+}
+
+/// A transport answering every everything request with `status` and `body`.
+pub fn everything(status: u16, body: &str) -> MemoryTransport {
+    let transport = MemoryTransport::new();
+    transport.route("/v2/everything", status, body);
+    transport
+}
+
+/// An article titled `Article {number}`, published `number` minutes after
+/// midnight on 2022-01-01.
+pub fn article(number: u32) -> serde_json::Value {
+    json!({
+        "source": {"id": null, "name": "Example"},
+        "title": format!("Article {}", number),
+        "author": null,
+        "description": null,
+        "url": format!("https://example.com/{}", number),
+        "urlToImage": null,
+        "publishedAt": format!("2022-01-01T{:02}:{:02}:00Z", number / 60 % 24, number % 60),
+        "content": null,
+    })
+}
+
+/// A page of `count` articles numbered from `first`, out of `total`.
+pub fn page(total: u32, first: u32, count: u32) -> String {
+    let articles: Vec<_> = (first..first + count).map(article).collect();
+    json!({"status": "ok", "totalResults": total, "articles": articles}).to_string()
+}
+
+/// A transport answering pages 1 to `pages` with two articles each, out of
+/// `total`.
+pub fn paged(total: u32, pages: u32) -> MemoryTransport {
+    let transport = MemoryTransport::new();
+    for i in 1..=pages {
+        transport.route(
+            &format!("/v2/everything?page={}", i),
+            200,
+            &page(total, 2 * i - 1, 2),
+        );
+    }
+    transport
+}
+
+/// The body NewsAPI sends for a failed request.
+pub fn error_body(code: &str, message: &str) -> String {
+    json!({"status": "error", "code": code, "message": message}).to_string()
+}
+
+/// The `name` query parameter of every request `transport` received.
+pub fn sent_param(transport: &MemoryTransport, name: &str) -> Vec<String> {
+    transport
+        .requests()
+        .iter()
+        .filter_map(|request| {
+            url::Url::parse(request.url())
+                .unwrap()
+                .query_pairs()
+                .find(|(param, _)| param == name)
+                .map(|(_, value)| value.into_owned())
+        })
+        .collect()
+}
+
+/// A path in the temporary directory that no other test uses, with nothing
+/// at it.
+pub fn temp_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("newsapi-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_dir_all(&path);
+    path
+}
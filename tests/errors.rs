@@ -0,0 +1,127 @@
+mod common;
+
+use common::{api, error_body, everything, request, EMPTY};
+use newsapi::{ApiResponse, NewsAPIResponse, NewsApiError};
+
+/// Whether an error is the one a case expects.
+type Expected = fn(&NewsApiError) -> bool;
+
+fn fetch_error(status: u16, body: &str) -> NewsApiError {
+    api(&everything(status, body))
+        .fetch(&request())
+        .unwrap_err()
+}
+
+#[test]
+fn error_codes_map_to_their_variants_with_the_message() {
+    let cases: Vec<(&str, Expected)> = vec![
+        (
+            "apiKeyDisabled",
+            |err| matches!(err, NewsApiError::ApiKeyDisabled(message) if message == "details"),
+        ),
+        ("apiKeyExhausted", |err| {
+            matches!(err, NewsApiError::ApiKeyExhausted(_))
+        }),
+        ("apiKeyInvalid", |err| {
+            matches!(err, NewsApiError::ApiKeyInvalid(_))
+        }),
+        ("apiKeyMissing", |err| {
+            matches!(err, NewsApiError::ApiKeyMissing(_))
+        }),
+        ("parameterInvalid", |err| {
+            matches!(err, NewsApiError::ParameterInvalid(_))
+        }),
+        ("parametersMissing", |err| {
+            matches!(err, NewsApiError::ParametersMissing(_))
+        }),
+        ("rateLimited", |err| {
+            matches!(err, NewsApiError::RateLimited(_))
+        }),
+        ("sourcesTooMany", |err| {
+            matches!(err, NewsApiError::SourcesTooMany(_))
+        }),
+        ("sourceDoesNotExist", |err| {
+            matches!(err, NewsApiError::SourceDoesNotExist(_))
+        }),
+        ("maximumResultsReached", |err| {
+            matches!(err, NewsApiError::MaximumResultsReached(_))
+        }),
+        ("unexpectedError", |err| {
+            matches!(err, NewsApiError::UnexpectedError(_))
+        }),
+    ];
+
+    for (code, expected) in cases {
+        let err = fetch_error(400, &error_body(code, "details"));
+        assert!(expected(&err), "{} gave {:?}", code, err);
+        assert!(err.to_string().ends_with(": details"), "{}", err);
+    }
+}
+
+#[test]
+fn unknown_error_codes_keep_code_and_message() {
+    match fetch_error(400, &error_body("somethingNew", "details")) {
+        NewsApiError::UnknownError { code, message } => {
+            assert_eq!(code, "somethingNew");
+            assert_eq!(message, "details");
+        }
+        err => panic!("expected UnknownError, got {:?}", err),
+    }
+}
+
+#[test]
+fn error_bodies_are_read_from_non_2xx_responses() {
+    for status in [400, 401, 426, 429, 500] {
+        let err = fetch_error(status, &error_body("rateLimited", "slow down"));
+        assert!(
+            matches!(&err, NewsApiError::RateLimited(message) if message == "slow down"),
+            "{} gave {:?}",
+            status,
+            err
+        );
+    }
+}
+
+#[test]
+fn bodies_that_are_not_api_errors_keep_the_http_status() {
+    for body in ["<html>Bad Gateway</html>", "", r#"{"unrelated":true}"#] {
+        match fetch_error(502, body) {
+            NewsApiError::HttpStatus { status, body: sent } => {
+                assert_eq!(status, 502);
+                assert_eq!(sent, body);
+            }
+            err => panic!("expected HttpStatus for {:?}, got {:?}", body, err),
+        }
+    }
+}
+
+#[test]
+fn error_payloads_with_a_success_status_are_errors() {
+    assert!(matches!(
+        fetch_error(200, &error_body("apiKeyMissing", "no key")),
+        NewsApiError::ApiKeyMissing(_)
+    ));
+}
+
+#[test]
+fn payloads_are_told_apart_by_their_status() {
+    let ok: ApiResponse<NewsAPIResponse> = serde_json::from_str(EMPTY).unwrap();
+    assert_eq!(ok.into_result().unwrap().total_results(), 0);
+
+    let error: ApiResponse<NewsAPIResponse> =
+        serde_json::from_str(&error_body("apiKeyInvalid", "bad key")).unwrap();
+    match error {
+        ApiResponse::Error(body) => {
+            assert_eq!(body.code(), "apiKeyInvalid");
+            assert_eq!(body.message(), "bad key");
+        }
+        ApiResponse::Ok(_) => panic!("expected an error payload"),
+    }
+
+    let unknown = r#"{"status":"maybe","totalResults":0,"articles":[]}"#;
+    assert!(serde_json::from_str::<ApiResponse<NewsAPIResponse>>(unknown).is_err());
+    assert!(matches!(
+        fetch_error(200, unknown),
+        NewsApiError::ParseError(_)
+    ));
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use common::{api, everything, request, temp_path, EMPTY};
+use newsapi::{DailyBudget, FileKind, MemoryTransport, NewsApiError, OverLimit, RateLimit};
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn rate_limits_allow_a_burst_then_fail_fast() {
+    let transport = everything(200, EMPTY);
+    let mut api = api(&transport);
+    api.rate_limit(RateLimit::new(2, Duration::from_secs(60)).on_limit(OverLimit::Fail));
+
+    api.fetch(&request()).unwrap();
+    api.fetch(&request()).unwrap();
+    match api.fetch(&request()).unwrap_err() {
+        NewsApiError::RateLimitExceeded(wait) => {
+            assert!(wait > Duration::from_secs(29) && wait <= Duration::from_secs(30))
+        }
+        err => panic!("expected RateLimitExceeded, got {:?}", err),
+    }
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn rate_limits_wait_for_the_next_token() {
+    let transport = everything(200, EMPTY);
+    let mut api = api(&transport);
+    api.rate_limit(RateLimit::new(1, Duration::from_millis(50)));
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        api.fetch(&request()).unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(90));
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[test]
+fn a_zero_rate_limit_denies_every_request() {
+    for on_limit in [OverLimit::Wait, OverLimit::Fail] {
+        let transport = everything(200, EMPTY);
+        let mut api = api(&transport);
+        api.rate_limit(RateLimit::new(0, Duration::from_secs(1)).on_limit(on_limit));
+        assert!(matches!(
+            api.fetch(&request()),
+            Err(NewsApiError::RateLimitExceeded(_))
+        ));
+        assert!(transport.requests().is_empty());
+    }
+}
+
+#[test]
+fn waits_too_long_for_a_duration_are_capped() {
+    let transport = everything(200, EMPTY);
+    let mut api = api(&transport);
+    api.rate_limit(RateLimit::new(1, Duration::from_secs(u64::MAX)).on_limit(OverLimit::Fail));
+
+    api.fetch(&request()).unwrap();
+    assert!(matches!(
+        api.fetch(&request()),
+        Err(NewsApiError::RateLimitExceeded(Duration::MAX))
+    ));
+}
+
+fn budgeted(transport: &MemoryTransport, limit: u32, path: &Path) -> newsapi::NewsAPI {
+    let mut api = api(transport);
+    api.daily_budget(DailyBudget::new(limit).persist(path));
+    api
+}
+
+#[test]
+fn budgets_fail_once_spent() {
+    let transport = everything(200, EMPTY);
+    let mut api = api(&transport);
+    api.daily_budget(DailyBudget::new(2));
+
+    api.fetch(&request()).unwrap();
+    api.fetch(&request()).unwrap();
+    assert!(matches!(
+        api.fetch(&request()),
+        Err(NewsApiError::BudgetExhausted(2))
+    ));
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn budget_counts_survive_a_new_budget_on_the_same_file() {
+    let path = temp_path("budget-restart.json");
+    let transport = everything(200, EMPTY);
+
+    let api = budgeted(&transport, 3, &path);
+    api.fetch(&request()).unwrap();
+    api.fetch(&request()).unwrap();
+    drop(api);
+
+    let api = budgeted(&transport, 3, &path);
+    api.fetch(&request()).unwrap();
+    assert!(matches!(
+        api.fetch(&request()),
+        Err(NewsApiError::BudgetExhausted(3))
+    ));
+    assert_eq!(transport.requests().len(), 3);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn budgets_sharing_a_file_lose_no_requests() {
+    let path = temp_path("budget-shared.json");
+    let transport = everything(200, EMPTY);
+
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let transport = transport.clone();
+            let path = path.clone();
+            thread::spawn(move || {
+                let api = budgeted(&transport, 40, &path);
+                for _ in 0..10 {
+                    api.fetch(&request()).unwrap();
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    assert!(matches!(
+        budgeted(&transport, 40, &path).fetch(&request()),
+        Err(NewsApiError::BudgetExhausted(40))
+    ));
+    assert_eq!(transport.requests().len(), 40);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn unreadable_budget_files_are_reported() {
+    let path = temp_path("budget-corrupt.json");
+    fs::write(&path, "not json").unwrap();
+
+    let transport = everything(200, EMPTY);
+    match budgeted(&transport, 1, &path)
+        .fetch(&request())
+        .unwrap_err()
+    {
+        NewsApiError::FileError {
+            kind: FileKind::Budget,
+            path: failed,
+            ..
+        } => assert_eq!(failed, path),
+        err => panic!("expected a budget FileError, got {:?}", err),
+    }
+    assert!(transport.requests().is_empty());
+    fs::remove_file(&path).unwrap();
+}
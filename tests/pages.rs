@@ -0,0 +1,127 @@
+mod common;
+
+use common::{api, error_body, page, paged, request, sent_param};
+use newsapi::{MemoryTransport, NewsApiError};
+
+#[test]
+fn pages_stop_at_the_page_holding_the_last_result() {
+    let transport = paged(5, 4);
+    let pages: Vec<_> = api(&transport)
+        .pages(request().page_size(2))
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(pages.len(), 3);
+    assert_eq!(sent_param(&transport, "page"), vec!["1", "2", "3"]);
+}
+
+#[test]
+fn pages_started_part_way_stop_at_the_same_last_page() {
+    let transport = paged(6, 4);
+    assert_eq!(
+        api(&transport)
+            .pages(request().page_size(2).page(3))
+            .count(),
+        1
+    );
+    assert_eq!(sent_param(&transport, "page"), vec!["3"]);
+}
+
+#[test]
+fn pages_default_to_the_largest_page_size() {
+    let transport = paged(2, 1);
+    api(&transport).pages(request()).for_each(drop);
+    assert_eq!(sent_param(&transport, "pageSize"), vec!["100"]);
+}
+
+#[test]
+fn pages_stop_at_an_empty_page() {
+    let transport = MemoryTransport::new();
+    transport
+        .route("/v2/everything?page=1", 200, &page(10, 1, 2))
+        .route("/v2/everything?page=2", 200, &page(10, 3, 0))
+        .route("/v2/everything?page=3", 200, &page(10, 3, 2));
+
+    assert_eq!(api(&transport).pages(request().page_size(2)).count(), 2);
+    assert_eq!(sent_param(&transport, "page"), vec!["1", "2"]);
+}
+
+#[test]
+fn pages_end_cleanly_at_the_result_ceiling() {
+    let transport = MemoryTransport::new();
+    transport
+        .route("/v2/everything?page=1", 200, &page(10, 1, 2))
+        .route(
+            "/v2/everything?page=2",
+            426,
+            &error_body("maximumResultsReached", "Upgrade"),
+        );
+
+    let pages: Vec<_> = api(&transport).pages(request().page_size(2)).collect();
+    assert_eq!(pages.len(), 1);
+    assert!(pages[0].is_ok());
+    assert_eq!(sent_param(&transport, "page"), vec!["1", "2"]);
+}
+
+#[test]
+fn pages_stop_after_the_first_error() {
+    let transport = MemoryTransport::new();
+    transport
+        .route("/v2/everything?page=1", 200, &page(10, 1, 2))
+        .route("/v2/everything?page=2", 500, "Internal Server Error")
+        .route("/v2/everything?page=3", 200, &page(10, 5, 2));
+    let api = api(&transport);
+
+    let mut pages = api.pages(request().page_size(2));
+    assert!(pages.next().unwrap().is_ok());
+    match pages.next().unwrap() {
+        Err(NewsApiError::HttpStatus { status: 500, .. }) => {}
+        page => panic!("expected an HTTP 500 error, got {:?}", page),
+    }
+    assert!(pages.next().is_none());
+    assert_eq!(sent_param(&transport, "page"), vec!["1", "2"]);
+}
+
+#[test]
+fn pages_respect_max_pages() {
+    let transport = paged(10, 5);
+    assert_eq!(
+        api(&transport)
+            .pages(request().page_size(2).page(2))
+            .max_pages(2)
+            .count(),
+        2
+    );
+    assert_eq!(sent_param(&transport, "page"), vec!["2", "3"]);
+}
+
+#[test]
+fn articles_follow_the_pages() {
+    let transport = paged(6, 4);
+    let titles: Vec<String> = api(&transport)
+        .articles(request().page_size(2))
+        .map(|article| article.unwrap().title().to_string())
+        .collect();
+    assert_eq!(
+        titles,
+        vec![
+            "Article 1",
+            "Article 2",
+            "Article 3",
+            "Article 4",
+            "Article 5",
+            "Article 6"
+        ]
+    );
+}
+
+#[test]
+fn articles_request_no_more_pages_than_max_articles_needs() {
+    let transport = paged(10, 5);
+    let titles: Vec<String> = api(&transport)
+        .articles(request().page_size(2))
+        .max_articles(3)
+        .map(|article| article.unwrap().title().to_string())
+        .collect();
+    assert_eq!(titles, vec!["Article 1", "Article 2", "Article 3"]);
+    assert_eq!(sent_param(&transport, "page"), vec!["1", "2"]);
+}
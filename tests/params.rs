@@ -0,0 +1,82 @@
+use newsapi::{Category, Country, Language, NewsApiError, SearchIn, SortBy};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+#[test]
+fn every_supported_value_is_covered() {
+    assert_eq!(Country::ALL.len(), 54);
+    assert_eq!(Language::ALL.len(), 14);
+    assert_eq!(Category::ALL.len(), 7);
+
+    let codes: HashSet<&str> = Country::ALL.iter().map(|country| country.code()).collect();
+    assert_eq!(codes.len(), Country::ALL.len());
+}
+
+#[test]
+fn values_parse_from_their_code_in_any_case() {
+    assert_eq!("se".parse::<Country>().unwrap(), Country::SE);
+    assert_eq!("SE".parse::<Country>().unwrap(), Country::SE);
+    assert_eq!("Business".parse::<Category>().unwrap(), Category::Business);
+    assert_eq!(
+        "publishedat".parse::<SortBy>().unwrap(),
+        SortBy::PublishedAt
+    );
+
+    for language in Language::ALL {
+        assert_eq!(language.to_string().parse::<Language>().unwrap(), *language);
+    }
+    for search_in in SearchIn::ALL {
+        assert_eq!(search_in.code().parse::<SearchIn>().unwrap(), *search_in);
+    }
+}
+
+#[test]
+fn unknown_values_list_the_accepted_ones() {
+    match "xx".parse::<Language>().unwrap_err() {
+        NewsApiError::InvalidValue {
+            kind,
+            value,
+            expected,
+        } => {
+            assert_eq!(kind, "language");
+            assert_eq!(value, "xx");
+            assert_eq!(
+                expected,
+                "ar, de, en, es, fr, he, it, nl, no, pt, ru, sv, ud, zh"
+            );
+        }
+        err => panic!("expected InvalidValue, got {:?}", err),
+    }
+    assert_eq!(
+        "weather".parse::<Category>().unwrap_err().to_string(),
+        "Invalid category `weather`, expected one of: business, entertainment, general, \
+         health, science, sports, technology"
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Settings {
+    country: Country,
+    languages: Vec<Language>,
+    category: Option<Category>,
+}
+
+#[test]
+fn values_serialize_as_their_code() {
+    let settings = Settings {
+        country: Country::US,
+        languages: vec![Language::EN, Language::SV],
+        category: Some(Category::Technology),
+    };
+    let json = serde_json::to_string(&settings).unwrap();
+    assert_eq!(
+        json,
+        r#"{"country":"us","languages":["en","sv"],"category":"technology"}"#
+    );
+    assert_eq!(serde_json::from_str::<Settings>(&json).unwrap(), settings);
+
+    let err =
+        serde_json::from_str::<Settings>(r#"{"country":"zz","languages":[],"category":null}"#)
+            .unwrap_err();
+    assert!(err.to_string().contains("Invalid country `zz`"), "{}", err);
+}
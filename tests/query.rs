@@ -0,0 +1,111 @@
+mod common;
+
+use common::{api, everything, sent_param, EMPTY};
+use newsapi::{EverythingRequest, NewsApiError, Query};
+
+fn term(term: &str) -> Query {
+    Query::term(term)
+}
+
+#[test]
+fn queries_render_with_the_parentheses_precedence_needs() {
+    let cases = vec![
+        (term("rust"), "rust"),
+        (
+            term("rust").and(term("go")).and(term("zig")),
+            "rust AND go AND zig",
+        ),
+        (
+            term("rust").or(term("go")).and(term("wasm")),
+            "(rust OR go) AND wasm",
+        ),
+        (
+            term("rust").and(term("go")).or(term("wasm")),
+            "(rust AND go) OR wasm",
+        ),
+        (
+            term("wasm").or(term("rust").and(term("go"))),
+            "wasm OR (rust AND go)",
+        ),
+        (
+            term("rust").or(term("go").or(term("zig"))),
+            "rust OR (go OR zig)",
+        ),
+        (Query::must(term("rust")), "+rust"),
+        (
+            Query::must_not(term("rust").or(term("go"))),
+            "-(rust OR go)",
+        ),
+        (!term("crypto"), "NOT crypto"),
+        (
+            term("rust").and(!term("game").or(term("engine"))),
+            "rust AND NOT (game OR engine)",
+        ),
+        (
+            term("rust").and(!term("game")).or(term("zig")),
+            "(rust AND NOT game) OR zig",
+        ),
+    ];
+    for (query, expected) in cases {
+        assert_eq!(query.build().unwrap(), expected);
+    }
+}
+
+#[test]
+fn terms_are_quoted_only_when_needed() {
+    let cases = vec![
+        (term("game engine"), "\"game engine\""),
+        (term("-rust"), "\"-rust\""),
+        (term("+rust"), "\"+rust\""),
+        (term("OR"), "\"OR\""),
+        (term("or"), "or"),
+        (term("(rust)"), "\"(rust)\""),
+        (Query::phrase("rust"), "\"rust\""),
+        (
+            Query::must(Query::phrase("game engine")),
+            "+\"game engine\"",
+        ),
+    ];
+    for (query, expected) in cases {
+        assert_eq!(query.build().unwrap(), expected);
+    }
+}
+
+#[test]
+fn queries_the_syntax_cannot_express_are_rejected() {
+    let violations = |query: Query| match query.build().unwrap_err() {
+        NewsApiError::InvalidRequest(violations) => violations,
+        err => panic!("expected InvalidRequest, got {:?}", err),
+    };
+
+    assert_eq!(
+        violations(term("say \"hi\"")),
+        vec!["term `say \"hi\"` contains a double quote, which the query syntax cannot express"]
+    );
+    assert_eq!(
+        violations(term(" ").and(Query::And(Vec::new()))),
+        vec![
+            "the query contains an empty term",
+            "the query contains an empty AND or OR group",
+        ]
+    );
+    let long = (0..200).fold(term("a"), |query, _| query.or(term("ab")));
+    assert_eq!(violations(long).len(), 1);
+}
+
+#[test]
+fn searches_send_the_rendered_query() {
+    let transport = everything(200, EMPTY);
+    let query = term("rust")
+        .or(term("go"))
+        .and(Query::must_not(term("game engine")));
+    api(&transport)
+        .fetch(&EverythingRequest::new().search(&query).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        sent_param(&transport, "q"),
+        vec!["(rust OR go) AND -\"game engine\""]
+    );
+    assert!(EverythingRequest::new().search(&term("\"")).is_err());
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::{everything, request, EMPTY};
+use newsapi::{ApiKey, HttpRequest, KeyPlacement, NewsAPI};
+
+const KEY: &str = "s3cret+key";
+
+fn client(placement: KeyPlacement) -> NewsAPI {
+    let mut api = NewsAPI::new(KEY);
+    api.key_placement(placement);
+    api
+}
+
+fn sent(placement: KeyPlacement) -> HttpRequest {
+    let transport = everything(200, EMPTY);
+    let mut api = client(placement);
+    api.transport(transport.clone());
+    api.fetch(&request()).unwrap();
+    transport.requests().remove(0)
+}
+
+fn header(request: &HttpRequest, name: &str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|(header, _)| header == name)
+        .map(|(_, value)| value.clone())
+}
+
+#[test]
+fn the_key_is_sent_where_placement_says() {
+    let request = sent(KeyPlacement::XApiKeyHeader);
+    assert_eq!(header(&request, "X-Api-Key").as_deref(), Some(KEY));
+    assert_eq!(header(&request, "Authorization"), None);
+    assert!(!request.url().contains("apiKey"));
+
+    let request = sent(KeyPlacement::BearerHeader);
+    assert_eq!(
+        header(&request, "Authorization"),
+        Some(format!("Bearer {}", KEY))
+    );
+    assert_eq!(header(&request, "X-Api-Key"), None);
+    assert!(!request.url().contains("apiKey"));
+
+    let request = sent(KeyPlacement::QueryParameter);
+    assert!(request.url().ends_with("&apiKey=s3cret%2Bkey"));
+    assert!(request
+        .headers()
+        .iter()
+        .all(|(_, value)| !value.contains(KEY)));
+}
+
+#[test]
+fn requests_do_not_debug_print_the_key() {
+    for placement in [
+        KeyPlacement::XApiKeyHeader,
+        KeyPlacement::BearerHeader,
+        KeyPlacement::QueryParameter,
+    ] {
+        let debug = format!("{:?}", sent(placement));
+        assert!(!debug.contains("s3cret"), "{}", debug);
+        assert!(debug.contains("***"), "{}", debug);
+    }
+}
+
+#[test]
+fn api_keys_do_not_print_themselves() {
+    let key = ApiKey::new(KEY);
+    assert_eq!(format!("{}", key), "***");
+    assert_eq!(format!("{:?}", key), "***");
+    assert_eq!(key.expose(), KEY);
+    assert_eq!(ApiKey::from(KEY.to_string()).expose(), KEY);
+}
+
+#[test]
+fn transport_errors_do_not_leak_a_key_in_the_url() {
+    let mut api = client(KeyPlacement::QueryParameter);
+    api.base_url("http://127.0.0.1:9/v2/");
+    let err = api.fetch(&request()).unwrap_err();
+
+    let text = format!("{} {:?}", err, err);
+    assert!(!text.contains("s3cret"), "{}", text);
+    assert!(err.is_retryable());
+}
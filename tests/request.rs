@@ -0,0 +1,167 @@
+mod common;
+
+use common::{api, request, EMPTY};
+use newsapi::{
+    AnyRequest, AnyResponse, Category, Country, EverythingRequest, Language, MemoryTransport,
+    NewsAPI, NewsApiError, SearchIn, SortBy, SourcesRequest, Timestamp, TopHeadlinesRequest,
+};
+
+fn time(time: &str) -> Timestamp {
+    time.parse().unwrap()
+}
+
+fn violations(err: NewsApiError) -> Vec<String> {
+    match err {
+        NewsApiError::InvalidRequest(violations) => violations,
+        err => panic!("expected InvalidRequest, got {:?}", err),
+    }
+}
+
+#[test]
+fn requests_round_trip_through_their_url() {
+    let api = NewsAPI::new("key");
+    let requests: Vec<AnyRequest> = vec![
+        TopHeadlinesRequest::new()
+            .country(Country::SE)
+            .category(Category::Business)
+            .query("Volvo")
+            .page_size(20)
+            .page(2)
+            .into(),
+        TopHeadlinesRequest::new()
+            .sources(&["bbc-news", "svd"])
+            .into(),
+        EverythingRequest::new()
+            .query("+rust -\"game engine\"")
+            .search_in(vec![SearchIn::Title, SearchIn::Content])
+            .domains(&["bbc.co.uk"])
+            .exclude_domains(&["example.com"])
+            .from(time("2022-01-01T00:00:00Z"))
+            .to(time("2022-02-01T10:30:00.5Z"))
+            .language(Language::EN)
+            .sort_by(SortBy::Popularity)
+            .into(),
+        SourcesRequest::new()
+            .category(Category::Science)
+            .language(Language::DE)
+            .country(Country::DE)
+            .into(),
+        SourcesRequest::new().into(),
+    ];
+
+    for request in requests {
+        let url = api.prepare_url(&request).unwrap();
+        let parsed = AnyRequest::from_url(&url).unwrap();
+        assert_eq!(parsed, request, "{}", url);
+        assert_eq!(api.prepare_url(&parsed).unwrap(), url);
+    }
+}
+
+#[test]
+fn from_url_accepts_other_base_urls_and_ignores_the_key() {
+    let parsed =
+        AnyRequest::from_url("http://127.0.0.1:8080/newsapi/v2/everything/?q=rust&apiKey=secret")
+            .unwrap();
+    assert_eq!(parsed, request().into());
+}
+
+#[test]
+fn from_url_reports_every_problem() {
+    let err = AnyRequest::from_url(
+        "https://newsapi.org/v2/everything?q=a&country=us&language=xx&pageSize=abc&q=b",
+    )
+    .unwrap_err();
+    let violations = violations(err);
+    assert_eq!(violations.len(), 4, "{:?}", violations);
+    assert!(violations[0].contains("`q` is given more than once"));
+    assert!(violations
+        .iter()
+        .any(|v| v.contains("Invalid language `xx`")));
+    assert!(violations.iter().any(|v| v.contains("pageSize `abc`")));
+    assert!(violations
+        .iter()
+        .any(|v| v == "unknown parameter `country`"));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn from_url_reports_times_that_do_not_parse() {
+    let err = AnyRequest::from_url("https://newsapi.org/v2/everything?q=a&from=soon").unwrap_err();
+    assert_eq!(violations(err), vec!["from `soon` is not a date or time"]);
+}
+
+#[test]
+fn from_url_rejects_unknown_endpoints() {
+    let violations = violations(AnyRequest::from_url("https://newsapi.org/v2/nope").unwrap_err());
+    assert_eq!(violations, vec!["`/v2/nope` is not a NewsAPI endpoint"]);
+}
+
+#[test]
+fn from_query_accepts_a_leading_question_mark() {
+    assert_eq!(
+        TopHeadlinesRequest::from_query("?country=us").unwrap(),
+        TopHeadlinesRequest::new().country(Country::US)
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn from_query_reads_a_date_as_midnight_utc() {
+    assert_eq!(
+        EverythingRequest::from_query("q=rust&from=2022-01-01").unwrap(),
+        request().from(time("2022-01-01T00:00:00Z"))
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn times_keep_their_fractional_seconds() {
+    let request = request()
+        .from(time("2022-01-01T00:00:00.5Z"))
+        .to(time("2022-01-01T02:00:01.000250+01:00"));
+    let url = NewsAPI::new("key").prepare_url(&request).unwrap();
+    let sent: Vec<(String, String)> = url::Url::parse(&url)
+        .unwrap()
+        .query_pairs()
+        .filter(|(name, _)| name == "from" || name == "to")
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+    assert_eq!(
+        sent,
+        vec![
+            ("from".to_string(), "2022-01-01T00:00:00.500Z".to_string()),
+            ("to".to_string(), "2022-01-01T01:00:01.000250Z".to_string()),
+        ]
+    );
+    assert_eq!(AnyRequest::from_url(&url).unwrap(), request.into());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn from_query_reads_fractional_seconds_without_an_offset_as_utc() {
+    assert_eq!(
+        EverythingRequest::from_query("q=rust&from=2022-01-01T12:00:00.25").unwrap(),
+        request().from(time("2022-01-01T12:00:00.25Z"))
+    );
+}
+
+#[test]
+fn any_request_fetches_the_matching_response() {
+    let transport = MemoryTransport::new();
+    transport.route(
+        "/v2/top-headlines/sources",
+        200,
+        r#"{"status":"ok","sources":[{"id":"svd","name":"SvD","description":"","url":"https://svd.se","category":"general","language":"sv","country":"se"}]}"#,
+    );
+    transport.route("/v2/everything", 200, EMPTY);
+    let api = api(&transport);
+
+    match api.fetch(&AnyRequest::from(SourcesRequest::new())).unwrap() {
+        AnyResponse::Sources(response) => assert_eq!(response.sources()[0].id(), "svd"),
+        response => panic!("expected sources, got {:?}", response),
+    }
+    match api.fetch(&AnyRequest::from(request())).unwrap() {
+        AnyResponse::Articles(response) => assert_eq!(response.total_results(), 0),
+        response => panic!("expected articles, got {:?}", response),
+    }
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::{api, error_body, everything, request};
+use newsapi::{HttpResponse, MemoryTransport, NewsAPI, NewsApiError, RetryPolicy};
+use std::time::Duration;
+
+fn retrying(transport: &MemoryTransport, max_attempts: u32) -> NewsAPI {
+    let mut api = api(transport);
+    api.retry(RetryPolicy::new(max_attempts).initial_backoff(Duration::from_millis(1)));
+    api
+}
+
+#[test]
+fn only_transient_failures_are_retryable() {
+    let retryable = |status: u16, body: &str| {
+        api(&everything(status, body))
+            .fetch(&request())
+            .unwrap_err()
+            .is_retryable()
+    };
+    assert!(retryable(429, &error_body("rateLimited", "")));
+    assert!(retryable(500, &error_body("unexpectedError", "")));
+    assert!(retryable(503, "Service Unavailable"));
+    assert!(retryable(429, "Too Many Requests"));
+    assert!(!retryable(401, &error_body("apiKeyInvalid", "")));
+    assert!(!retryable(426, &error_body("maximumResultsReached", "")));
+    assert!(!retryable(404, "Not Found"));
+}
+
+#[test]
+fn retryable_failures_are_retried_up_to_the_policy() {
+    let transport = everything(503, "Service Unavailable");
+    let err = retrying(&transport, 3).fetch(&request()).unwrap_err();
+    assert!(matches!(err, NewsApiError::HttpStatus { status: 503, .. }));
+    assert_eq!(transport.requests().len(), 3);
+}
+
+#[test]
+fn other_failures_are_not_retried() {
+    let transport = everything(401, &error_body("apiKeyInvalid", ""));
+    retrying(&transport, 3).fetch(&request()).unwrap_err();
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn retry_after_is_honoured_unless_it_is_too_long() {
+    let transport = MemoryTransport::new();
+    transport.route_response(
+        "/v2/everything",
+        HttpResponse::new(429, &error_body("rateLimited", "")).with_header("Retry-After", "0"),
+    );
+    retrying(&transport, 2).fetch(&request()).unwrap_err();
+    assert_eq!(transport.requests().len(), 2);
+
+    let transport = MemoryTransport::new();
+    transport.route_response(
+        "/v2/everything",
+        HttpResponse::new(429, &error_body("rateLimited", "")).with_header("Retry-After", "3600"),
+    );
+    retrying(&transport, 2).fetch(&request()).unwrap_err();
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn local_failures_are_not_retried() {
+    let mut api = retrying(&MemoryTransport::new(), 3);
+    api.base_url("ftp://127.0.0.1:9/v2/");
+    api.transport(ureq::Agent::new());
+    let err = api.fetch(&request()).unwrap_err();
+    assert!(!err.is_retryable(), "{:?}", err);
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use common::{api, error_body, page, paged, request, sent_param};
+use futures::executor::block_on;
+use futures::StreamExt;
+use newsapi::{MemoryTransport, NewsApiError};
+
+fn titles(articles: Vec<Result<newsapi::Article, NewsApiError>>) -> Vec<String> {
+    articles
+        .into_iter()
+        .map(|article| article.unwrap().title().to_string())
+        .collect()
+}
+
+#[test]
+fn page_streams_stop_at_the_page_holding_the_last_result() {
+    let transport = paged(6, 4);
+    let api = api(&transport);
+    let pages: Vec<_> = block_on(api.pages_async(request().page_size(2)).collect());
+    assert_eq!(pages.len(), 3);
+    assert_eq!(sent_param(&transport, "page"), vec!["1", "2", "3"]);
+
+    let transport = paged(6, 4);
+    let api = common::api(&transport);
+    let pages: Vec<_> = block_on(
+        api.pages_async(request().page_size(2).page(3))
+            .prefetch(true)
+            .collect(),
+    );
+    assert_eq!(pages.len(), 1);
+    assert_eq!(sent_param(&transport, "page"), vec!["3"]);
+}
+
+#[test]
+fn prefetching_requests_the_next_page_while_one_is_consumed() {
+    block_on(async {
+        let transport = paged(6, 4);
+        let api = api(&transport);
+        let mut pages = api.pages_async(request().page_size(2)).prefetch(true);
+        pages.next().await.unwrap().unwrap();
+        assert_eq!(sent_param(&transport, "page"), vec!["1", "2"]);
+        assert_eq!(pages.count().await, 2);
+        assert_eq!(sent_param(&transport, "page"), vec!["1", "2", "3"]);
+
+        let transport = paged(6, 4);
+        let api = common::api(&transport);
+        let mut pages = api.pages_async(request().page_size(2));
+        pages.next().await.unwrap().unwrap();
+        assert_eq!(sent_param(&transport, "page"), vec!["1"]);
+    });
+}
+
+#[test]
+fn article_streams_follow_the_pages() {
+    let transport = paged(4, 3);
+    let api = api(&transport);
+    let articles = block_on(
+        api.articles_async(request().page_size(2))
+            .prefetch(true)
+            .collect(),
+    );
+    assert_eq!(
+        titles(articles),
+        vec!["Article 1", "Article 2", "Article 3", "Article 4"]
+    );
+    assert_eq!(sent_param(&transport, "page"), vec!["1", "2"]);
+}
+
+#[test]
+fn article_streams_request_no_more_pages_than_max_articles_needs() {
+    for prefetch in [false, true] {
+        let transport = paged(10, 5);
+        let api = api(&transport);
+        let articles = block_on(
+            api.articles_async(request().page_size(2))
+                .prefetch(prefetch)
+                .max_articles(3)
+                .collect(),
+        );
+        assert_eq!(
+            titles(articles),
+            vec!["Article 1", "Article 2", "Article 3"]
+        );
+        assert_eq!(sent_param(&transport, "page"), vec!["1", "2"]);
+    }
+}
+
+#[test]
+fn streams_end_cleanly_at_the_result_ceiling() {
+    let transport = MemoryTransport::new();
+    transport
+        .route("/v2/everything?page=1", 200, &page(10, 1, 2))
+        .route(
+            "/v2/everything?page=2",
+            426,
+            &error_body("maximumResultsReached", "Upgrade"),
+        );
+    let api = api(&transport);
+
+    let articles = block_on(
+        api.articles_async(request().page_size(2))
+            .prefetch(true)
+            .collect(),
+    );
+    assert_eq!(titles(articles), vec!["Article 1", "Article 2"]);
+    assert_eq!(sent_param(&transport, "page"), vec!["1", "2"]);
+}
+
+#[test]
+fn streams_stop_after_the_first_error() {
+    let transport = MemoryTransport::new();
+    transport
+        .route("/v2/everything?page=1", 200, &page(10, 1, 2))
+        .route("/v2/everything?page=2", 500, "Internal Server Error");
+    let api = api(&transport);
+
+    let pages: Vec<_> = block_on(
+        api.pages_async(request().page_size(2))
+            .max_pages(5)
+            .collect(),
+    );
+    assert_eq!(pages.len(), 2);
+    assert!(matches!(
+        pages[1],
+        Err(NewsApiError::HttpStatus { status: 500, .. })
+    ));
+    assert_eq!(sent_param(&transport, "page"), vec!["1", "2"]);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{api, request, EMPTY};
+use newsapi::{HttpRequest, HttpResponse, MemoryTransport, NewsApiError, Transport};
+
+#[test]
+fn routes_match_on_path_and_their_own_query_pairs() {
+    let transport = MemoryTransport::new();
+    transport
+        .route("/v2/everything?page=2", 200, r#"{"page":2}"#)
+        .route("/v2/everything", 200, r#"{"page":"any"}"#);
+
+    let send = |url: &str| transport.send(&HttpRequest::new(url)).unwrap();
+    assert_eq!(
+        send("https://newsapi.org/v2/everything?q=rust&page=2").body(),
+        r#"{"page":2}"#
+    );
+    assert_eq!(
+        send("https://newsapi.org/v2/everything?q=rust&page=3").body(),
+        r#"{"page":"any"}"#
+    );
+    assert_eq!(
+        send("https://newsapi.org/v2/everything").body(),
+        r#"{"page":"any"}"#
+    );
+}
+
+#[test]
+fn unrouted_requests_get_a_404() {
+    let transport = MemoryTransport::new();
+    transport.route("/v2/top-headlines", 200, EMPTY);
+    let response = transport
+        .send(&HttpRequest::new("https://newsapi.org/v2/everything"))
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    match api(&transport).fetch(&request()).unwrap_err() {
+        NewsApiError::HttpStatus { status: 404, .. } => {}
+        err => panic!("expected a 404, got {:?}", err),
+    }
+}
+
+#[test]
+fn clones_share_routes_and_the_request_log() {
+    let transport = MemoryTransport::new();
+    let api = api(&transport);
+    transport.route_response("/v2/everything", HttpResponse::new(200, EMPTY));
+
+    api.fetch(&request()).unwrap();
+    api.fetch(&request().page(2)).unwrap();
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        requests[0].url(),
+        "https://newsapi.org/v2/everything?q=rust"
+    );
+    assert_eq!(
+        requests[1].url(),
+        "https://newsapi.org/v2/everything?q=rust&page=2"
+    );
+}
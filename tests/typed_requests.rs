@@ -0,0 +1,193 @@
+mod common;
+
+use common::{api, page, sent_param};
+use newsapi::{
+    Category, Country, Endpoint, EverythingRequest, Language, MemoryTransport, PagedRequest,
+    Request, SearchIn, SortBy, SourcesRequest, TopHeadlinesRequest,
+};
+use std::collections::HashSet;
+use std::thread;
+
+fn owned(pairs: &[(&'static str, &str)]) -> Vec<(&'static str, String)> {
+    pairs
+        .iter()
+        .map(|(name, value)| (*name, value.to_string()))
+        .collect()
+}
+
+#[test]
+fn builders_return_a_new_request_and_leave_the_original_alone() {
+    let base = TopHeadlinesRequest::new().country(Country::SE);
+    let business = base.clone().category(Category::Business);
+    assert_eq!(base, TopHeadlinesRequest::new().country(Country::SE));
+    assert_ne!(base, business);
+    assert_eq!(base.query_pairs(), owned(&[("country", "se")]));
+}
+
+#[test]
+fn each_request_knows_its_endpoint() {
+    assert_eq!(
+        TopHeadlinesRequest::new().endpoint(),
+        Endpoint::TopHeadlines
+    );
+    assert_eq!(EverythingRequest::new().endpoint(), Endpoint::Everything);
+    assert_eq!(SourcesRequest::new().endpoint(), Endpoint::Sources);
+}
+
+#[test]
+fn query_pairs_follow_a_fixed_order_whatever_the_builder_order() {
+    let top_headlines = TopHeadlinesRequest::new()
+        .page(2)
+        .query("volvo")
+        .page_size(20)
+        .category(Category::Business)
+        .country(Country::SE);
+    assert_eq!(
+        top_headlines.query_pairs(),
+        owned(&[
+            ("country", "se"),
+            ("category", "business"),
+            ("q", "volvo"),
+            ("pageSize", "20"),
+            ("page", "2"),
+        ])
+    );
+
+    let everything = EverythingRequest::new()
+        .sort_by(SortBy::Relevancy)
+        .language(Language::EN)
+        .exclude_domains(&["example.com"])
+        .domains(&["bbc.co.uk", "svd.se"])
+        .sources(&["bbc-news"])
+        .search_in(vec![SearchIn::Title, SearchIn::Description])
+        .query("rust");
+    assert_eq!(
+        everything.query_pairs(),
+        owned(&[
+            ("q", "rust"),
+            ("searchIn", "title,description"),
+            ("sources", "bbc-news"),
+            ("domains", "bbc.co.uk,svd.se"),
+            ("excludeDomains", "example.com"),
+            ("language", "en"),
+            ("sortBy", "relevancy"),
+        ])
+    );
+
+    let sources = SourcesRequest::new()
+        .country(Country::DE)
+        .language(Language::DE)
+        .category(Category::Science);
+    assert_eq!(
+        sources.query_pairs(),
+        owned(&[
+            ("category", "science"),
+            ("language", "de"),
+            ("country", "de"),
+        ])
+    );
+}
+
+#[test]
+fn with_paging_changes_only_the_page_and_its_size() {
+    let request = EverythingRequest::new()
+        .query("rust")
+        .language(Language::EN);
+    assert_eq!(request.paging(), (None, None));
+
+    let paged = request.with_paging(3, 50);
+    assert_eq!(paged.paging(), (Some(3), Some(50)));
+    assert_eq!(paged, request.clone().page(3).page_size(50));
+    assert_eq!(request.paging(), (None, None));
+
+    let top_headlines = TopHeadlinesRequest::new().country(Country::US).page(9);
+    assert_eq!(
+        top_headlines.with_paging(1, 10).paging(),
+        (Some(1), Some(10))
+    );
+}
+
+#[test]
+fn requests_survive_a_serde_round_trip() {
+    let top_headlines = TopHeadlinesRequest::new()
+        .sources(&["bbc-news", "svd"])
+        .query("volvo")
+        .page_size(10);
+    let json = serde_json::to_string(&top_headlines).unwrap();
+    assert_eq!(
+        serde_json::from_str::<TopHeadlinesRequest>(&json).unwrap(),
+        top_headlines
+    );
+
+    let everything = EverythingRequest::new()
+        .query("rust")
+        .search_in(vec![SearchIn::Content])
+        .sort_by(SortBy::Popularity);
+    let json = serde_json::to_string(&everything).unwrap();
+    assert_eq!(
+        serde_json::from_str::<EverythingRequest>(&json).unwrap(),
+        everything
+    );
+
+    let sources = SourcesRequest::new().language(Language::SV);
+    let json = serde_json::to_string(&sources).unwrap();
+    assert_eq!(
+        serde_json::from_str::<SourcesRequest>(&json).unwrap(),
+        sources
+    );
+    assert_eq!(
+        serde_json::from_str::<SourcesRequest>("{}").unwrap(),
+        SourcesRequest::new()
+    );
+}
+
+#[test]
+fn equal_requests_hash_alike() {
+    let searches: HashSet<EverythingRequest> = vec![
+        EverythingRequest::new().query("rust").page(2),
+        EverythingRequest::new().page(2).query("rust"),
+        EverythingRequest::new().query("rust"),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(searches.len(), 2);
+}
+
+#[test]
+fn one_client_serves_concurrent_requests() {
+    let transport = MemoryTransport::new();
+    for i in 1..=4 {
+        transport.route(
+            &format!("/v2/everything?page={}", i),
+            200,
+            &page(8, 2 * i - 1, 2),
+        );
+    }
+    let api = api(&transport);
+
+    let titles: Vec<String> = thread::scope(|scope| {
+        let handles: Vec<_> = (1..=4)
+            .map(|i| {
+                let api = &api;
+                scope.spawn(move || {
+                    let request = EverythingRequest::new().query("rust").page(i);
+                    api.fetch(&request).unwrap().articles()[0]
+                        .title()
+                        .to_string()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+    assert_eq!(
+        titles,
+        vec!["Article 1", "Article 3", "Article 5", "Article 7"]
+    );
+
+    let mut pages = sent_param(&transport, "page");
+    pages.sort();
+    assert_eq!(pages, vec!["1", "2", "3", "4"]);
+}
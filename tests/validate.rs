@@ -0,0 +1,96 @@
+mod common;
+
+use common::{api, everything, EMPTY};
+use newsapi::{Country, EverythingRequest, NewsAPI, NewsApiError, TopHeadlinesRequest};
+
+fn violations(err: NewsApiError) -> Vec<String> {
+    match err {
+        NewsApiError::InvalidRequest(violations) => violations,
+        err => panic!("expected InvalidRequest, got {:?}", err),
+    }
+}
+
+#[test]
+fn validation_lists_every_violation() {
+    let api = NewsAPI::new("key");
+    let sources: Vec<String> = (0..21).map(|i| format!("source-{}", i)).collect();
+    let sources: Vec<&str> = sources.iter().map(String::as_str).collect();
+    let request = TopHeadlinesRequest::new()
+        .sources(&sources)
+        .country(Country::US)
+        .page_size(101)
+        .page(0);
+    assert_eq!(
+        violations(api.prepare_url(&request).unwrap_err()),
+        vec![
+            "21 sources given, the maximum is 20",
+            "pageSize is 101, it must be between 1 and 100",
+            "page is 0, pages start at 1",
+            "sources cannot be mixed with country or category",
+        ]
+    );
+
+    assert_eq!(
+        violations(api.prepare_url(&TopHeadlinesRequest::new()).unwrap_err()),
+        vec!["top-headlines needs at least one of country, category, sources or q"]
+    );
+}
+
+#[test]
+fn a_blank_query_counts_as_missing() {
+    let api = NewsAPI::new("key");
+    for q in ["", "   "] {
+        assert_eq!(
+            violations(
+                api.prepare_url(&TopHeadlinesRequest::new().query(q))
+                    .unwrap_err()
+            ),
+            vec!["top-headlines needs at least one of country, category, sources or q"],
+            "{:?}",
+            q
+        );
+        assert_eq!(
+            violations(
+                api.prepare_url(&EverythingRequest::new().query(q))
+                    .unwrap_err()
+            ),
+            vec!["everything needs at least one of q, sources or domains"],
+            "{:?}",
+            q
+        );
+    }
+
+    assert!(api
+        .prepare_url(&TopHeadlinesRequest::new().query(" ").country(Country::SE))
+        .is_ok());
+    assert!(api
+        .prepare_url(&EverythingRequest::new().query("").domains(&["bbc.co.uk"]))
+        .is_ok());
+}
+
+#[test]
+fn invalid_requests_are_not_sent() {
+    let transport = everything(200, EMPTY);
+    let err = api(&transport)
+        .fetch(&EverythingRequest::new().page_size(0))
+        .unwrap_err();
+    assert_eq!(
+        violations(err),
+        vec![
+            "pageSize is 0, it must be between 1 and 100",
+            "everything needs at least one of q, sources or domains",
+        ]
+    );
+    assert!(transport.requests().is_empty());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn validation_rejects_from_after_to() {
+    let request = EverythingRequest::new()
+        .from("2022-02-01T00:00:00Z".parse().unwrap())
+        .to("2022-01-01T00:00:00Z".parse().unwrap());
+    let violations = violations(NewsAPI::new("key").prepare_url(&request).unwrap_err());
+    assert_eq!(violations.len(), 2);
+    assert!(violations[1].starts_with("from (2022-02-01 00:00:00 UTC) is after to"));
+}
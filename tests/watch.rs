@@ -0,0 +1,175 @@
+mod common;
+
+use common::{article, request, temp_path};
+use newsapi::{FileKind, HttpRequest, HttpResponse, NewsAPI, NewsApiError, Transport};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Answers the n-th request with the n-th response, repeating the last one.
+#[derive(Clone)]
+struct Polls {
+    responses: Arc<Vec<(u16, String)>>,
+    sent: Arc<Mutex<usize>>,
+}
+
+impl Polls {
+    fn new(responses: Vec<(u16, String)>) -> Polls {
+        Polls {
+            responses: Arc::new(responses),
+            sent: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    fn sent(&self) -> usize {
+        *self.sent.lock().unwrap()
+    }
+}
+
+impl Transport for Polls {
+    fn send(&self, _: &HttpRequest) -> Result<HttpResponse, NewsApiError> {
+        let responses = &self.responses;
+        let mut sent = self.sent.lock().unwrap();
+        let (status, body) = &responses[(*sent).min(responses.len() - 1)];
+        *sent += 1;
+        Ok(HttpResponse::new(*status, body))
+    }
+}
+
+/// A successful poll returning `articles`.
+fn poll(articles: Vec<serde_json::Value>) -> (u16, String) {
+    let body = json!({"status": "ok", "totalResults": articles.len(), "articles": articles});
+    (200, body.to_string())
+}
+
+/// Article `number` under a different link to the same page.
+fn relinked(number: u32, url: &str) -> serde_json::Value {
+    let mut article = article(number);
+    article["url"] = json!(url);
+    article
+}
+
+fn api(polls: &Polls) -> NewsAPI {
+    let mut api = NewsAPI::new("key");
+    api.transport(polls.clone());
+    api
+}
+
+fn titles<I: Iterator<Item = Result<newsapi::Article, NewsApiError>>>(
+    watch: I,
+    count: usize,
+) -> Vec<String> {
+    watch
+        .take(count)
+        .map(|article| article.unwrap().title().to_string())
+        .collect()
+}
+
+const INTERVAL: Duration = Duration::from_millis(1);
+
+#[test]
+fn each_article_is_yielded_once_oldest_first() {
+    let polls = Polls::new(vec![
+        poll(vec![article(2), article(1)]),
+        poll(vec![
+            relinked(1, "https://example.com/1#comments"),
+            relinked(2, "https://example.com/2?utm_source=feed&utm_medium=rss"),
+            article(3),
+        ]),
+    ]);
+    let api = api(&polls);
+
+    let watch = api.watch(request(), INTERVAL);
+    assert_eq!(
+        titles(watch, 3),
+        vec!["Article 1", "Article 2", "Article 3"]
+    );
+    assert_eq!(polls.sent(), 2);
+}
+
+#[test]
+fn other_query_parameters_tell_articles_apart() {
+    let polls = Polls::new(vec![poll(vec![
+        relinked(1, "https://example.com/story?id=1&utm_source=feed"),
+        relinked(2, "https://example.com/story?id=2"),
+    ])]);
+    let api = api(&polls);
+
+    let watch = api.watch(request(), INTERVAL);
+    assert_eq!(titles(watch, 2), vec!["Article 1", "Article 2"]);
+}
+
+#[test]
+fn a_restarted_watcher_skips_what_the_state_file_holds() {
+    let path = temp_path("watch-restart.json");
+    let polls = Polls::new(vec![
+        poll(vec![article(1), article(2)]),
+        poll(vec![article(1), article(2)]),
+        poll(vec![article(1), article(2), article(3)]),
+    ]);
+    let api = api(&polls);
+
+    let watch = api.watch(request(), INTERVAL).state_file(&path).unwrap();
+    assert_eq!(titles(watch, 2), vec!["Article 1", "Article 2"]);
+    let saved: Vec<String> =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(
+        saved,
+        vec!["https://example.com/1", "https://example.com/2"]
+    );
+
+    let watch = api.watch(request(), INTERVAL).state_file(&path).unwrap();
+    assert_eq!(titles(watch, 1), vec!["Article 3"]);
+    assert_eq!(polls.sent(), 3);
+}
+
+#[test]
+fn only_the_newest_max_seen_articles_are_remembered() {
+    let polls = Polls::new(vec![
+        poll(vec![article(1)]),
+        poll(vec![article(2)]),
+        poll(vec![article(1)]),
+    ]);
+    let api = api(&polls);
+
+    let watch = api.watch(request(), INTERVAL).max_seen(1);
+    assert_eq!(
+        titles(watch, 3),
+        vec!["Article 1", "Article 2", "Article 1"]
+    );
+}
+
+#[test]
+fn failed_polls_are_yielded_and_polling_carries_on() {
+    let polls = Polls::new(vec![
+        (500, "Internal Server Error".to_string()),
+        poll(vec![article(1)]),
+    ]);
+    let api = api(&polls);
+
+    let mut watch = api.watch(request(), INTERVAL);
+    assert!(matches!(
+        watch.next(),
+        Some(Err(NewsApiError::HttpStatus { status: 500, .. }))
+    ));
+    assert_eq!(watch.next().unwrap().unwrap().title(), "Article 1");
+}
+
+#[test]
+fn a_corrupt_state_file_is_an_error() {
+    let path = temp_path("watch-corrupt.json");
+    std::fs::write(&path, "not json").unwrap();
+    let polls = Polls::new(vec![poll(vec![])]);
+    let api = api(&polls);
+
+    match api.watch(request(), INTERVAL).state_file(&path) {
+        Err(NewsApiError::FileError {
+            kind: FileKind::WatchState,
+            path: failed,
+            ..
+        }) => assert_eq!(failed, path),
+        Err(err) => panic!("expected a watch state FileError, got {:?}", err),
+        Ok(_) => panic!("expected a watch state FileError"),
+    }
+    assert_eq!(polls.sent(), 0);
+}